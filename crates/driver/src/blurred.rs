@@ -20,11 +20,7 @@ fn main() -> anyhow::Result<()> {
     );
     let mut colors: Array<vec3, _> = Array::zeros((x, y).f());
 
-    while let Some(i) = tracer.scene.step() {
-        println!("[raytrace] timestep {}", i);
-        // dbg!(&tracer.scene);
-        // wait_for_input();
-
+    let render = |colors: &mut Array<vec3, _>, tracer: &TracerAnimated| {
         colors
             .indexed_iter_mut()
             .progress()
@@ -35,7 +31,22 @@ fn main() -> anyhow::Result<()> {
                 let color = tracer.color_at(ix as u32, iy as u32, &mut rng).to_gamma();
                 *px += color.vector();
             });
-    }
+    };
+
+    let n_pass = if tracer.cam.shutter.x < tracer.cam.shutter.y {
+        // rays are spread over the shutter, a single pass is blurred already
+        println!("[raytrace] shutter {:?}", tracer.cam.shutter);
+        render(&mut colors, &tracer);
+        1
+    } else {
+        while let Some(i) = tracer.scene.step() {
+            println!("[raytrace] timestep {}", i);
+            // dbg!(&tracer.scene);
+            // wait_for_input();
+            render(&mut colors, &tracer);
+        }
+        tracer.n_step
+    };
 
     let mut img = RgbImage::new(tracer.cam.resolution.x, tracer.cam.resolution.y);
 
@@ -43,7 +54,7 @@ fn main() -> anyhow::Result<()> {
         .progress()
         .for_each(|(ix, iy, px)| {
             let index = (ix as usize, iy as usize);
-            let color = colors[index] / tracer.n_step as f64;
+            let color = colors[index] / n_pass as f64;
             *px = color.to_gamma().quantize_u8().into()
         });
 
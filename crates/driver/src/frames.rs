@@ -0,0 +1,49 @@
+use rand::rngs::ThreadRng;
+use raytrace::tracer::{film::Film, tracers::tracer_animated::TracerAnimated};
+use std::{fs, time::Instant};
+mod debug;
+
+fn run() -> anyhow::Result<()> {
+    let mut tracer = TracerAnimated::configured("config/keyframed/tracer.toml")?;
+    let frames = tracer
+        .frames
+        .clone()
+        .expect("Expect a [frames] table in the tracer config");
+
+    fs::create_dir_all(&frames.out_dir)?;
+
+    for i in 0..frames.n_frames {
+        let path = frames.path(i);
+        if frames.skip_existing && path.exists() {
+            println!("[raytrace] frame {} exists, skipped", i);
+            continue;
+        }
+
+        // rays are timed by the shutter, the scene itself is never stepped
+        tracer.cam.shutter = frames.shutter(i);
+        println!("[raytrace] frame {} at time {}", i, frames.frame_time(i));
+
+        // ########################### Main work ###########################
+        let film = Film::splat(tracer.cam.resolution, None, &tracer.filter, |ix, iy| {
+            let mut rng = ThreadRng::default();
+            tracer.samples_at(ix, iy, &mut rng)
+        });
+        let img = film.developed(&tracer.cam);
+        // ######################### Main work end #########################
+
+        // renamed once complete, so an interrupted save is not taken for a done frame
+        let partial = path.with_extension("partial.png");
+        img.save(&partial)?;
+        fs::rename(&partial, &path)?;
+    }
+
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    let start = Instant::now();
+    run()?;
+    let duration = start.elapsed();
+    println!("Execution time: {:?}", duration);
+    Ok(())
+}
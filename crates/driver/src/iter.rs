@@ -1,8 +1,9 @@
-use image::RgbImage;
-use indicatif::ParallelProgressIterator;
 use rand::rngs::ThreadRng;
-use rayon::iter::ParallelIterator;
-use raytrace::{helpers::traits::Color, tracer::tracers::tracer_iter::TracerIter};
+use raytrace::tracer::{
+    aov::{AovFilm, Pass},
+    film::Film,
+    tracers::tracer_iter::TracerIter,
+};
 use std::time::Instant;
 mod debug;
 
@@ -10,18 +11,47 @@ fn run() -> anyhow::Result<()> {
     let tracer = TracerIter::configured("config/litup/tracer.toml")?;
 
     // ########################### Main work ###########################
-    let mut img = RgbImage::new(tracer.cam.resolution.x, tracer.cam.resolution.y);
-    img.par_enumerate_pixels_mut()
-        .progress()
-        .for_each(|(ix, iy, px)| {
+    let region = tracer.region.as_ref();
+    let split = tracer
+        .aov
+        .as_ref()
+        .is_some_and(|aov| aov.passes.iter().any(Pass::is_light));
+    let (film, light) = if split {
+        // the light passes are weighted along the image
+        let [film, emission, direct, indirect] =
+            Film::splat_layers(tracer.cam.resolution, region, &tracer.filter, |ix, iy| {
+                let mut rng = ThreadRng::default();
+                tracer.light_samples_at(ix, iy, &mut rng)
+            });
+        (film, Some([emission, direct, indirect]))
+    } else {
+        let film = Film::splat(tracer.cam.resolution, region, &tracer.filter, |ix, iy| {
             let mut rng = ThreadRng::default();
-
-            let color = tracer.color_at(ix, iy, &mut rng).to_gamma();
-            *px = color.quantize_u8().into()
+            tracer.samples_at(ix, iy, &mut rng)
         });
+        (film, None)
+    };
+    // first hits guide denoising and fill the other passes
+    let first_hits = (tracer.denoise.is_some() || tracer.aov.is_some()).then(|| {
+        AovFilm::render(tracer.cam.resolution, region, |ix, iy| {
+            let mut rng = ThreadRng::default();
+            tracer.first_hit_at(ix, iy, &mut rng)
+        })
+    });
+    let film = match (&tracer.denoise, &first_hits) {
+        (Some(denoise), Some(guide)) => denoise.apply(&film, guide),
+        _ => film,
+    };
     // ######################### Main work end #########################
 
-    img.save(tracer.out_path)?;
+    film.save(&tracer.cam, &tracer.out_path, region)?;
+
+    if let (Some(aov), Some(mut aov_film)) = (&tracer.aov, first_hits) {
+        if let Some(light) = &light {
+            aov_film.add_light(light);
+        }
+        aov_film.save(aov, &tracer.cam)?;
+    }
     Ok(())
 }
 
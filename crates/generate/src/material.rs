@@ -2,7 +2,9 @@ use std::{fs, io::Write};
 
 use rand::{thread_rng, Rng};
 use raytrace::{
-    config::materials::MaterialMap, helpers::types::color, materials::material::Material,
+    config::materials::MaterialMap,
+    helpers::types::color,
+    materials::material::{Material, MaterialKind},
 };
 
 fn random_color<R: Rng>(rng: &mut R) -> color {
@@ -13,19 +15,19 @@ fn random_color<R: Rng>(rng: &mut R) -> color {
     )
 }
 
-fn generate_random_material<R: Rng>(rng: &mut R) -> Material {
+fn generate_random_material<R: Rng>(rng: &mut R) -> MaterialKind {
     match rng.gen_range(0..4) {
-        0 => Material::Lambertian {
+        0 => MaterialKind::Lambertian {
             albedo: random_color(rng),
         },
-        1 => Material::Metal {
+        1 => MaterialKind::Metal {
             albedo: random_color(rng),
         },
-        2 => Material::FuzzedMetal {
+        2 => MaterialKind::FuzzedMetal {
             albedo: random_color(rng),
             fuzz: rng.gen_range(0.0..=0.4),
         },
-        3 => Material::Dielectric {
+        3 => MaterialKind::Dielectric {
             eta: {
                 let scale = rng.gen_range(1.0..2.0);
                 let inv = rng.gen_bool(0.5);
@@ -50,7 +52,10 @@ fn main() {
             .into_iter()
             .map(|i| {
                 let name = format!("mat{}", i);
-                let mat = generate_random_material(&mut rng);
+                let mat = Material {
+                    id: i as u32 + 1,
+                    kind: generate_random_material(&mut rng),
+                };
                 (name, mat)
             })
             .collect(),
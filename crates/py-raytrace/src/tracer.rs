@@ -2,20 +2,20 @@ use pyo3::pymodule;
 
 #[pymodule]
 pub mod tracer {
-    use image::RgbImage;
-    use indicatif::ParallelProgressIterator;
     use pyo3::{
-        exceptions::{PyException, PyIOError},
+        exceptions::{PyException, PyIOError, PyValueError},
         pyclass, pymethods, PyResult,
     };
     use rand::rngs::ThreadRng;
-    use rayon::iter::ParallelIterator;
     use raytrace::{
         camera::camera_lens::{LensCamera, LensCameraBuilder},
         entity::scene::Scene as RenderScene,
-        helpers::traits::Color,
         math::angles::deg2rad,
-        tracer::tracers::tracer_iter::TracerIter,
+        tracer::{
+            film::Film,
+            region::{Rect, Region},
+            tracers::tracer_iter::TracerIter,
+        },
     };
 
     #[pyclass]
@@ -48,6 +48,14 @@ pub mod tracer {
                 pos: pos.into(),
                 lookat: lookat.into(),
                 up: up.into(),
+                shutter: [0.0, 0.0].into(),
+                path: None,
+                projection: Default::default(),
+                stereo: None,
+                lens: None,
+                distortion: None,
+                tilt_shift: None,
+                exposure: None,
             };
             Ok(Self { cam: cb.build() })
         }
@@ -70,19 +78,51 @@ pub mod tracer {
 
     #[pymethods]
     impl RayTracer {
+        /// - `region`: pixels `[x0, y0, x1, y1]` to trace, the end excluded.
+        /// - `crop`: the same in \[0, 1\] of the image size.
+        /// - `composite`: pastes the region over the image at `out_path` instead of cropping.
         #[new]
+        #[pyo3(signature = (cam, scene, spp, out_path, region=None, crop=None, composite=false))]
         pub fn new(
             cam: &mut Camera,
             scene: &mut Scene,
             spp: usize,
             out_path: String,
+            region: Option<[u32; 4]>,
+            crop: Option<[f64; 4]>,
+            composite: bool,
         ) -> PyResult<Self> {
+            let rect = match (region, crop) {
+                (Some(_), Some(_)) => {
+                    return Err(PyValueError::new_err("Set either region or crop, not both"))
+                }
+                (Some([x0, y0, x1, y1]), None) => Some(Rect::Pixels {
+                    min: [x0, y0].into(),
+                    max: [x1, y1].into(),
+                }),
+                (None, Some([x0, y0, x1, y1])) => Some(Rect::Crop {
+                    min: [x0, y0].into(),
+                    max: [x1, y1].into(),
+                }),
+                (None, None) => None,
+            };
+            let region = rect.map(|rect| Region { rect, composite });
+            if let Some(region) = &region {
+                if region.is_empty(cam.cam.resolution) {
+                    return Err(PyValueError::new_err("Empty render region"));
+                }
+            }
+
             let res = Self {
                 tracer: TracerIter {
                     cam: cam.cam.clone(),
                     scene: scene.scene.clone(),
                     spp,
                     out_path,
+                    filter: Default::default(),
+                    region,
+                    denoise: None,
+                    aov: None,
                 },
             };
 
@@ -91,19 +131,15 @@ pub mod tracer {
 
         pub fn render(&self) -> PyResult<()> {
             // ########################### Main work ###########################
-            let mut img =
-                RgbImage::new(self.tracer.cam.resolution.x, self.tracer.cam.resolution.y);
-            img.par_enumerate_pixels_mut()
-                .progress()
-                .for_each(|(ix, iy, px)| {
-                    let mut rng = ThreadRng::default();
-
-                    let color = self.tracer.color_at(ix, iy, &mut rng).to_gamma();
-                    *px = color.quantize_u8().into()
-                });
+            let region = self.tracer.region.as_ref();
+            let (resolution, filter) = (self.tracer.cam.resolution, &self.tracer.filter);
+            let film = Film::splat(resolution, region, filter, |ix, iy| {
+                let mut rng = ThreadRng::default();
+                self.tracer.samples_at(ix, iy, &mut rng)
+            });
             // ######################### Main work end #########################
 
-            img.save(&self.tracer.out_path)
+            film.save(&self.tracer.cam, &self.tracer.out_path, region)
                 .map_err(|e| PyIOError::new_err(e.to_string()))?;
             Ok(())
         }
@@ -1,12 +1,30 @@
 use std::fs;
 
-use super::image_space::ImageSpace;
+use super::{
+    distortion::Distortion,
+    exposure::Exposure,
+    image_space::ImageSpace,
+    lens::{Aperture, Autofocus, Lens},
+    path::CameraPath,
+    projection::{cubemap, equirectangular, fisheye, Projection},
+    stereo::Stereo,
+    tilt_shift::TiltShift,
+};
 use crate::{
+    config::{gltf_import::GltfScene, toml_common::value_get_into_option},
     helpers::types::{vec2, vec3},
-    math::{angles::deg2rad, distributions::sample_on_disk, panics::PanickingNormalize},
+    math::{
+        angles::{deg2rad, rad2deg},
+        distributions::sample_uniform_01,
+        keyframes::{Animatable, Track},
+        panics::PanickingNormalize,
+        transform::Transform,
+    },
+    tracer::ray::{hit::Hit, ray::Ray},
 };
 use rand::rngs::ThreadRng;
 use serde::{Deserialize, Serialize};
+use toml::Value;
 
 #[derive(Serialize, Deserialize)]
 pub struct LensCameraBuilder {
@@ -22,13 +40,88 @@ pub struct LensCameraBuilder {
     // directions
     pub lookat: vec3,
     pub up: vec3,
+
+    /// Animation times the shutter opens and closes at, \[0, 0\] for no motion blur.
+    #[serde(default = "default_shutter")]
+    pub shutter: vec2,
+
+    /// Moves the camera over animation time.
+    #[serde(default)]
+    pub path: Option<CameraPath>,
+
+    #[serde(default)]
+    pub projection: Projection,
+
+    /// Renders both eyes side by side or over under.
+    #[serde(default)]
+    pub stereo: Option<Stereo>,
+
+    /// Physical lens taking over `yfov`, `viewport_distance` and `defocus_angle`.
+    #[serde(default)]
+    pub lens: Option<Lens>,
+
+    #[serde(default)]
+    pub distortion: Option<Distortion>,
+
+    #[serde(default)]
+    pub tilt_shift: Option<TiltShift>,
+
+    /// Brightness the film is developed with, as rendered if not set.
+    #[serde(default)]
+    pub exposure: Option<Exposure>,
+}
+
+fn default_shutter() -> vec2 {
+    vec2::zeros()
 }
 
 impl LensCameraBuilder {
+    /// With `gltf = "path"`, the pose and field of view are taken from camera
+    /// `gltf_camera` (default 0) of that file.
     pub fn configured(path: &str) -> anyhow::Result<Self> {
-        let mut res: LensCameraBuilder = toml::from_str(&fs::read_to_string(path)?)?;
+        let mut val: Value = toml::from_str(&fs::read_to_string(path)?)?;
+        if let Some(gltf_path) = value_get_into_option::<String>(&val, "gltf") {
+            let index = value_get_into_option(&val, "gltf_camera").unwrap_or(0);
+            let cam = GltfScene::open(&gltf_path)?.camera(index)?;
+
+            let table = val
+                .as_table_mut()
+                .expect("Expect camera config to be a table");
+            let vector = |v: vec3| Value::try_from([v.x, v.y, v.z]).unwrap();
+            table.insert("pos".into(), vector(cam.pos));
+            table.insert("lookat".into(), vector(cam.lookat));
+            table.insert("up".into(), vector(cam.up));
+            table.insert("yfov".into(), Value::Float(rad2deg(cam.yfov)));
+        }
+
+        let mut res: LensCameraBuilder = val.try_into()?;
         res.yfov = deg2rad(res.yfov);
         res.defocus_angle = deg2rad(res.defocus_angle);
+
+        // the viewport is the plane in focus
+        if let Some(lens) = &res.lens {
+            res.yfov = lens.yfov();
+            if let Some(focus_distance) = lens.focus_distance {
+                res.viewport_distance = focus_distance;
+            }
+            if let Some(Autofocus::Point { point }) = lens.autofocus {
+                res.viewport_distance = (point - res.pos).dot(&res.lookat.p_normalize());
+            }
+        }
+
+        if let Some(distortion) = &res.distortion {
+            let eye_resolution = match &res.stereo {
+                Some(stereo) => stereo.eye_resolution(res.resolution),
+                None => res.resolution,
+            };
+            let aspect = eye_resolution.x as f64 / eye_resolution.y as f64;
+            let shift = res.tilt_shift.as_ref().map_or(vec2::zeros(), |ts| ts.shift);
+            // farthest image corner over the viewport distance
+            let corner = vec2::new((0.5 + shift.x.abs()) * aspect, 0.5 + shift.y.abs())
+                * 2.0
+                * (0.5 * res.yfov).tan();
+            distortion.validate(corner.magnitude())?;
+        }
         Ok(res)
     }
 
@@ -41,6 +134,7 @@ impl LensCameraBuilder {
 pub struct LensCamera {
     /// Lens
     radius: f64,
+    defocus_angle: f64,
 
     pub resolution: glm::UVec2,
     /// ### In radians
@@ -58,49 +152,403 @@ pub struct LensCamera {
     pub right: vec3,
 
     pub image_space: ImageSpace,
+
+    pub shutter: vec2,
+    pub path: Option<CameraPath>,
+    pub projection: Projection,
+    pub stereo: Option<Stereo>,
+    /// Resolution of each eye, the whole image without stereo.
+    pub eye_resolution: glm::UVec2,
+    pub lens: Option<Lens>,
+    aperture: Aperture,
+    pub distortion: Option<Distortion>,
+    pub tilt_shift: Option<TiltShift>,
+    pub exposure: Option<Exposure>,
 }
 
-impl LensCamera {
-    pub fn new(cam: LensCameraBuilder) -> Self {
-        let mut cam = cam;
-        cam.lookat = cam.lookat.p_normalize();
-        cam.up = cam.up.p_normalize();
+/// Cosine between a ray and the plane in focus below which it is left untilted.
+const TILT_EPS: f64 = 1e-6;
 
-        // camera
-        let right = cam.lookat.cross(&cam.up).p_normalize();
+/// Where the camera stands and the image it frames, what moves along its path.
+#[derive(Clone)]
+struct Pose {
+    pos: vec3,
+    lookat: vec3,
+    up: vec3,
+    right: vec3,
+    vd: f64,
+    image_span: vec2,
+    image_space: ImageSpace,
+}
 
-        let yspan = 2.0 * cam.viewport_distance * (0.5 * cam.yfov).tan();
-        let xspan = yspan * (cam.resolution.x as f64) / cam.resolution.y as f64;
+impl Pose {
+    /// - `projection`, `eye_resolution`: of the camera, to frame the image of an eye.
+    fn new(
+        pos: vec3,
+        lookat: vec3,
+        up: vec3,
+        yfov: f64,
+        viewport_distance: f64,
+        projection: &Projection,
+        eye_resolution: glm::UVec2,
+    ) -> Self {
+        let lookat = lookat.p_normalize();
+        let up = up.p_normalize();
+        let right = lookat.cross(&up).p_normalize();
+
+        let aspect = eye_resolution.x as f64 / eye_resolution.y as f64;
+        let (xspan, yspan, image_center) = match projection {
+            // panoramas use the viewport of a perspective camera for nothing but its span
+            Projection::Perspective
+            | Projection::Equirectangular
+            | Projection::Fisheye { .. }
+            | Projection::Cubemap => {
+                let yspan = 2.0 * viewport_distance * (0.5 * yfov).tan();
+                (yspan * aspect, yspan, pos + lookat * viewport_distance)
+            }
+            // the image is where the parallel rays start from
+            Projection::Orthographic { width } => (*width, width / aspect, pos),
+        };
         let image_span = vec2::new(xspan, yspan);
 
         // image space
         let image_space = ImageSpace {
             xdir: right,
-            ydir: -cam.up,
-            //   cam pos  +  viewport to cam dist -     half Y      - half X
-            orig: cam.pos + cam.lookat * cam.viewport_distance + cam.up * yspan * 0.5
-                - right * xspan * 0.5,
-            delta: yspan / cam.resolution.y as f64,
+            ydir: -up,
+            //    center    +     half Y      - half X
+            orig: image_center + up * yspan * 0.5 - right * xspan * 0.5,
+            delta: yspan / eye_resolution.y as f64,
         };
 
         Self {
-            radius: cam.viewport_distance * (cam.defocus_angle * 0.5).tan(),
+            pos,
+            lookat,
+            up,
+            right,
+            vd: viewport_distance,
+            image_span,
+            image_space,
+        }
+    }
+}
+
+impl LensCamera {
+    pub fn new(cam: LensCameraBuilder) -> Self {
+        let aperture = Aperture::new(cam.lens.as_ref());
+        Self::with_aperture(cam, aperture)
+    }
+
+    /// Keeps an aperture already built, which may have been read from an image.
+    fn with_aperture(cam: LensCameraBuilder, aperture: Aperture) -> Self {
+        // the viewport is the one of an eye
+        let eye_resolution = match &cam.stereo {
+            Some(stereo) => stereo.eye_resolution(cam.resolution),
+            None => cam.resolution,
+        };
+        let pose = Pose::new(
+            cam.pos,
+            cam.lookat,
+            cam.up,
+            cam.yfov,
+            cam.viewport_distance,
+            &cam.projection,
+            eye_resolution,
+        );
+
+        Self {
+            radius: match &cam.lens {
+                Some(lens) => lens.aperture_radius(),
+                None => cam.viewport_distance * (cam.defocus_angle * 0.5).tan(),
+            },
+            defocus_angle: cam.defocus_angle,
             resolution: cam.resolution,
             yfov: cam.yfov,
-            pos: cam.pos,
-            lookat: cam.lookat,
-            up: cam.up,
-            vd: cam.viewport_distance,
+            pos: pose.pos,
+            lookat: pose.lookat,
+            up: pose.up,
+            vd: pose.vd,
 
-            image_span,
-            right,
-            image_space,
+            image_span: pose.image_span,
+            right: pose.right,
+            image_space: pose.image_space,
+
+            shutter: cam.shutter,
+            path: cam.path,
+            projection: cam.projection,
+            stereo: cam.stereo,
+            eye_resolution,
+            lens: cam.lens,
+            aperture,
+            distortion: cam.distortion,
+            tilt_shift: cam.tilt_shift,
+            exposure: cam.exposure,
+        }
+    }
+
+    /// The same camera placed and focused differently.
+    fn moved(&self, pos: vec3, lookat: vec3, yfov: f64, viewport_distance: f64) -> Self {
+        let cam = LensCameraBuilder {
+            defocus_angle: self.defocus_angle,
+            resolution: self.resolution,
+            yfov,
+            viewport_distance,
+            pos,
+            lookat,
+            up: self.up,
+            shutter: self.shutter,
+            path: self.path.clone(),
+            projection: self.projection.clone(),
+            stereo: self.stereo.clone(),
+            lens: self.lens.clone(),
+            distortion: self.distortion.clone(),
+            tilt_shift: self.tilt_shift.clone(),
+            exposure: self.exposure.clone(),
+        };
+        Self::with_aperture(cam, self.aperture.clone())
+    }
+
+    /// Focuses on the entity seen through the autofocus pixel, if the lens has one.
+    /// - `hit`: nearest hit of a ray in the scene.
+    pub fn autofocused(self, hit: impl Fn(Ray) -> Option<Hit>) -> Self {
+        let Some(Autofocus::Entity { pixel }) =
+            self.lens.as_ref().and_then(|l| l.autofocus.clone())
+        else {
+            return self;
+        };
+        let pixel = pixel.unwrap_or(self.eye_resolution / 2);
+        let target = self.image_space.pixel_lefttop_at(pixel.x, pixel.y)
+            + self.image_space.pixel_offset(0.5, 0.5);
+        let dir = (target - self.pos).p_normalize();
+
+        match hit(Ray::new(self.pos, dir, 0.0)) {
+            Some(hit) => {
+                let distance = (hit.pos - self.pos).dot(&self.lookat);
+                self.moved(self.pos, self.lookat, self.yfov, distance)
+            }
+            // nothing to focus on
+            None => self,
+        }
+    }
+
+    /// Where the camera stands at animation `time`, moved along its path if it has one.
+    fn pose_at(&self, time: f64) -> Pose {
+        let Some(path) = &self.path else {
+            return Pose {
+                pos: self.pos,
+                lookat: self.lookat,
+                up: self.up,
+                right: self.right,
+                vd: self.vd,
+                image_span: self.image_span,
+                image_space: self.image_space.clone(),
+            };
+        };
+        fn sample<T: Animatable>(track: &Option<Track<T>>, fixed: T, time: f64) -> T {
+            track.as_ref().map_or(fixed, |track| track.sample(time))
         }
+
+        let pos = sample(&path.pos, self.pos, time);
+        let lookat = match &path.target {
+            Some(target) => target.sample(time) - pos,
+            None => sample(&path.lookat, self.lookat, time),
+        };
+        let yfov = match &path.yfov {
+            Some(yfov) => deg2rad(yfov.sample(time)),
+            None => self.yfov,
+        };
+
+        let viewport_distance = sample(&path.focus_distance, self.vd, time);
+        Pose::new(
+            pos,
+            lookat,
+            self.up,
+            yfov,
+            viewport_distance,
+            &self.projection,
+            self.eye_resolution,
+        )
     }
 
-    pub fn sample_position(&self, rng: &mut ThreadRng) -> vec3 {
-        let delta_unit = sample_on_disk(rng);
-        let delta = delta_unit.x * self.right + delta_unit.y * self.up;
-        self.pos + self.radius * delta
+    /// Ray through a random point `(dx, dy)` in pixel `(ix, iy)`, from a random
+    /// point of the lens at a random time of the shutter.
+    /// `None` where the projection covers no pixel, e.g. around a fisheye circle.
+    pub fn sample_ray(
+        &self,
+        ix: u32,
+        iy: u32,
+        dx: f64,
+        dy: f64,
+        rng: &mut ThreadRng,
+    ) -> Option<Ray> {
+        let time = self.sample_time(rng);
+        let pose = self.pose_at(time);
+
+        // lateral shift of the eye, 0 without stereo
+        let (eye, ix, iy) = match &self.stereo {
+            Some(stereo) => {
+                let (side, ix, iy) = stereo.eye_of(ix, iy, self.eye_resolution);
+                (stereo.eye_offset(side), ix, iy)
+            }
+            None => (0.0, ix, iy),
+        };
+        let convergence = self
+            .stereo
+            .as_ref()
+            .and_then(|stereo| stereo.convergence)
+            .unwrap_or(f64::INFINITY);
+
+        let pixel =
+            pose.image_space.pixel_lefttop_at(ix, iy) + pose.image_space.pixel_offset(dx, dy);
+        let (u, v) = (
+            (ix as f64 + dx) / self.eye_resolution.x as f64,
+            (iy as f64 + dy) / self.eye_resolution.y as f64,
+        );
+
+        // directions in camera space, panoramas have no lens
+        let local = match self.projection {
+            Projection::Perspective => {
+                let pixel = self.lens_corrected(&pose, pixel, u, v);
+                let cam_pos = self.sample_position(&pose, rng) + eye * pose.right;
+                // the image of each eye is shifted so they meet at the convergence distance
+                let pixel = pixel + eye * (1.0 - pose.vd / convergence) * pose.right;
+                let dir = (pixel - cam_pos).p_normalize();
+                return Some(Ray::new(cam_pos, dir, 0.0).with_time(time));
+            }
+            Projection::Orthographic { .. } => {
+                let orig = pixel + eye * pose.right;
+                return Some(Ray::new(orig, pose.lookat, 0.0).with_time(time));
+            }
+            Projection::Equirectangular => equirectangular(u, v),
+            Projection::Fisheye { fov, mapping } => {
+                let aspect = self.eye_resolution.x as f64 / self.eye_resolution.y as f64;
+                fisheye(u, v, aspect, fov, mapping)?
+            }
+            Projection::Cubemap => cubemap(u, v),
+        };
+
+        let up = pose.right.cross(&pose.lookat);
+        let dir = (local.x * pose.right + local.y * up + local.z * pose.lookat).p_normalize();
+        if eye == 0.0 {
+            return Some(Ray::new(pose.pos, dir, 0.0).with_time(time));
+        }
+
+        // omni-directional stereo: the eyes turn with the horizontal direction
+        // looked at, staying on a circle of the interpupillary diameter
+        let horizontal = dir - dir.dot(&up) * up;
+        let offset = match horizontal.cross(&up).try_normalize(1e-12) {
+            Some(side) => eye * side,
+            // straight up or down, both eyes meet
+            None => vec3::zeros(),
+        };
+        let dir = if convergence.is_finite() {
+            (dir * convergence - offset).p_normalize()
+        } else {
+            dir
+        };
+        Some(Ray::new(pose.pos + offset, dir, 0.0).with_time(time))
+    }
+
+    /// Point in focus seen at `(u, v)` of the image through the distortion and the
+    /// tilt-shift of the lens, `pixel` without them.
+    fn lens_corrected(&self, pose: &Pose, pixel: vec3, u: f64, v: f64) -> vec3 {
+        if self.distortion.is_none() && self.tilt_shift.is_none() {
+            return pixel;
+        }
+        let center = pose.pos + pose.lookat * pose.vd;
+        let p = self.viewport_coords(pose, u, v);
+        let pixel = center + pose.vd * (p.x * pose.right + p.y * pose.up);
+
+        let Some(TiltShift { tilt, .. }) = &self.tilt_shift else {
+            return pixel;
+        };
+        if *tilt == vec2::zeros() {
+            return pixel;
+        }
+        // where the ray through the lens center meets the tilted plane in focus
+        let normal = Transform::rotate(deg2rad(tilt.y), pose.up)
+            .then(&Transform::rotate(deg2rad(tilt.x), pose.right))
+            .vector(pose.lookat);
+        let chief = pixel - pose.pos;
+        let facing = chief.dot(&normal);
+        // the ray runs along the plane or meets it behind the camera, focus untilted
+        if facing <= TILT_EPS * chief.magnitude() {
+            return pixel;
+        }
+        let t = (center - pose.pos).dot(&normal) / facing;
+        if !t.is_finite() || t <= 0.0 {
+            return pixel;
+        }
+        pose.pos + t * chief
+    }
+
+    /// Viewport coordinates over its distance, y up, of the point seen at `(u, v)`
+    /// of the image through the shift and the distortion of the lens.
+    fn viewport_coords(&self, pose: &Pose, u: f64, v: f64) -> vec2 {
+        let shift = self
+            .tilt_shift
+            .as_ref()
+            .map_or(vec2::zeros(), |ts| ts.shift);
+        let p = vec2::new(
+            (u - 0.5 + shift.x) * pose.image_span.x,
+            (0.5 - v + shift.y) * pose.image_span.y,
+        ) / pose.vd;
+        match &self.distortion {
+            Some(distortion) => distortion.undistort(p),
+            None => p,
+        }
+    }
+
+    /// f-number the film is exposed with.
+    pub fn f_number(&self) -> f64 {
+        self.exposure
+            .as_ref()
+            .and_then(|exposure| exposure.f_number)
+            .or(self.lens.as_ref().map(|lens| lens.f_number))
+            .unwrap_or(1.0)
+    }
+
+    /// Share of the light reaching pixel `(ix, iy)`, 1 without vignetting.
+    /// A moving camera is taken as posed in the middle of the shutter.
+    pub fn vignetting(&self, ix: u32, iy: u32) -> f64 {
+        let vignetting = self.exposure.as_ref().is_some_and(|e| e.vignetting);
+        if !vignetting || !matches!(self.projection, Projection::Perspective) {
+            return 1.0;
+        }
+        let (ix, iy) = match &self.stereo {
+            Some(stereo) => {
+                let (_, ix, iy) = stereo.eye_of(ix, iy, self.eye_resolution);
+                (ix, iy)
+            }
+            None => (ix, iy),
+        };
+
+        let pose = self.pose_at(0.5 * (self.shutter.x + self.shutter.y));
+        let p = self.viewport_coords(
+            &pose,
+            (ix as f64 + 0.5) / self.eye_resolution.x as f64,
+            (iy as f64 + 0.5) / self.eye_resolution.y as f64,
+        );
+        // cos^4 of the angle off the optical axis
+        let cos2 = 1.0 / (1.0 + p.norm_squared());
+        cos2 * cos2
+    }
+
+    /// Uniform within the shutter interval.
+    pub fn sample_time(&self, rng: &mut ThreadRng) -> f64 {
+        if self.shutter.x == self.shutter.y {
+            return self.shutter.x;
+        }
+        self.shutter.x + (self.shutter.y - self.shutter.x) * sample_uniform_01(rng)
+    }
+
+    fn sample_position(&self, pose: &Pose, rng: &mut ThreadRng) -> vec3 {
+        // the cone of the defocus angle follows the focus, a lens aperture does not
+        let radius = match &self.lens {
+            Some(_) => self.radius,
+            None => pose.vd * (self.defocus_angle * 0.5).tan(),
+        };
+        let delta_unit = self.aperture.sample(rng);
+        let delta = delta_unit.x * pose.right + delta_unit.y * pose.up;
+        pose.pos + radius * delta
     }
 }
@@ -0,0 +1,80 @@
+use crate::{config::errors::SerdeError, helpers::types::vec2};
+use serde::{Deserialize, Serialize};
+
+/// Brown-Conrady lens distortion, set by the `[distortion]` table of a camera with the
+/// coefficients of a calibrated photograph. Missing coefficients are 0.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Distortion {
+    /// Radial, negative for barrel and positive for pincushion distortion.
+    pub k1: f64,
+    pub k2: f64,
+    pub k3: f64,
+    /// Tangential, from a lens not parallel to the sensor.
+    pub p1: f64,
+    pub p2: f64,
+}
+
+/// Iterations inverting the distortion, plenty for photographic lenses.
+const UNDISTORT_ITERATIONS: usize = 8;
+
+/// Radial scale below which the lens would fold the image over itself.
+const MIN_RADIAL: f64 = 1e-3;
+
+/// Radii checked for a fold between the image center and its corner.
+const FOLD_CHECKS: usize = 64;
+
+impl Distortion {
+    /// Where the lens moves `p`, in coordinates of the viewport over its distance.
+    pub fn distort(&self, p: vec2) -> vec2 {
+        p * self.radial(p) + self.tangential(p)
+    }
+
+    /// The point the lens moves to `p`, found by fixed-point iteration.
+    pub fn undistort(&self, p: vec2) -> vec2 {
+        let mut undistorted = p;
+        for _ in 0..UNDISTORT_ITERATIONS {
+            let radial = self.radial(undistorted);
+            // past the fold there is no point the lens moves to `p`
+            if radial < MIN_RADIAL {
+                break;
+            }
+            undistorted = (p - self.tangential(undistorted)) / radial;
+        }
+        undistorted
+    }
+
+    /// Fails on coefficients that are not finite or fold the image over itself
+    /// within `r_max`, the radius of the image corners over the viewport distance.
+    pub fn validate(&self, r_max: f64) -> Result<(), SerdeError> {
+        let invalid = |reason: &str| SerdeError::Invalid {
+            field: "distortion".into(),
+            reason: reason.into(),
+        };
+        let coefficients = [self.k1, self.k2, self.k3, self.p1, self.p2];
+        if !coefficients.iter().all(|c| c.is_finite()) {
+            return Err(invalid("coefficients must be finite"));
+        }
+        let folds = (0..=FOLD_CHECKS)
+            .map(|i| r_max * i as f64 / FOLD_CHECKS as f64)
+            .any(|r| self.radial(vec2::new(r, 0.0)) < MIN_RADIAL);
+        if folds {
+            return Err(invalid("k1, k2 and k3 fold the image over itself"));
+        }
+        Ok(())
+    }
+
+    fn radial(&self, p: vec2) -> f64 {
+        let r2 = p.norm_squared();
+        1.0 + r2 * (self.k1 + r2 * (self.k2 + r2 * self.k3))
+    }
+
+    fn tangential(&self, p: vec2) -> vec2 {
+        let r2 = p.norm_squared();
+        let (x, y) = (p.x, p.y);
+        vec2::new(
+            2.0 * self.p1 * x * y + self.p2 * (r2 + 2.0 * x * x),
+            self.p1 * (r2 + 2.0 * y * y) + 2.0 * self.p2 * x * y,
+        )
+    }
+}
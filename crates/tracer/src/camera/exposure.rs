@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+/// Photographic exposure of the film, set by the `[exposure]` table of a camera.
+///
+/// Radiance is taken as calibrated for f/1, 1 s and ISO 100, the exposure value 0:
+/// those settings develop the image as rendered, each stop above halves it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exposure {
+    #[serde(default = "default_iso")]
+    pub iso: f64,
+    /// ### In seconds
+    /// Only scales the brightness, motion blur follows the `shutter` of the camera.
+    #[serde(default = "default_shutter_time")]
+    pub shutter_time: f64,
+    /// The f-number of the lens if not set, else 1.
+    pub f_number: Option<f64>,
+    /// ### In stops
+    #[serde(default)]
+    pub compensation: f64,
+
+    /// Meters the rendered image instead, so its average luminance develops to `key`.
+    #[serde(default)]
+    pub auto: bool,
+    #[serde(default = "default_key")]
+    pub key: f64,
+
+    /// Natural cos^4 falloff towards the edges of perspective images.
+    #[serde(default)]
+    pub vignetting: bool,
+}
+
+fn default_iso() -> f64 {
+    100.0
+}
+
+fn default_shutter_time() -> f64 {
+    1.0
+}
+
+fn default_key() -> f64 {
+    0.18
+}
+
+impl Exposure {
+    /// Exposure value at ISO 100.
+    pub fn ev100(&self, f_number: f64) -> f64 {
+        (f_number * f_number / self.shutter_time * 100.0 / self.iso).log2()
+    }
+
+    /// Factor radiance is developed with.
+    /// - `average_luminance`: log-average luminance of the image, for auto exposure.
+    pub fn scale(&self, f_number: f64, average_luminance: impl FnOnce() -> f64) -> f64 {
+        let scale = if self.auto {
+            let average = average_luminance();
+            if average > 0.0 {
+                self.key / average
+            } else {
+                1.0
+            }
+        } else {
+            (-self.ev100(f_number)).exp2()
+        };
+        scale * self.compensation.exp2()
+    }
+}
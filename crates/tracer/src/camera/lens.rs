@@ -0,0 +1,158 @@
+use crate::{
+    helpers::types::{vec2, vec3},
+    math::{
+        angles::deg2rad,
+        distributions::{sample_on_disk, sample_uniform_01},
+    },
+};
+use rand::rngs::ThreadRng;
+use serde::{Deserialize, Serialize};
+use std::{f64::consts::TAU, sync::Arc};
+
+/// A physical thin lens, set by the `[lens]` table of a camera.
+/// It takes over `yfov`, `viewport_distance` and `defocus_angle`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lens {
+    /// ### In millimeters
+    pub focal_length: f64,
+    /// Focal length over the aperture diameter.
+    pub f_number: f64,
+    /// ### In millimeters, 24 for a full frame
+    #[serde(default = "default_sensor_height")]
+    pub sensor_height: f64,
+    /// World units in a millimeter, 0.001 for scenes in meters.
+    #[serde(default = "default_unit")]
+    pub unit: f64,
+
+    /// Distance of the plane in focus, `viewport_distance` if not set.
+    pub focus_distance: Option<f64>,
+    /// Takes over `focus_distance`.
+    pub autofocus: Option<Autofocus>,
+
+    /// Polygonal aperture with this many blades, round below 3.
+    #[serde(default)]
+    pub blades: u32,
+    /// ### In degrees
+    #[serde(default)]
+    pub blade_rotation: f64,
+    /// Grayscale image of the aperture, takes over `blades`.
+    pub aperture_image: Option<String>,
+}
+
+fn default_sensor_height() -> f64 {
+    24.0
+}
+
+fn default_unit() -> f64 {
+    0.001
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Autofocus {
+    /// Focuses at the depth of `point`.
+    Point { point: vec3 },
+    /// Focuses on the entity seen through `pixel`, the center of the image if not set.
+    Entity { pixel: Option<glm::UVec2> },
+}
+
+impl Lens {
+    /// ### In radians
+    pub fn yfov(&self) -> f64 {
+        2.0 * (0.5 * self.sensor_height / self.focal_length).atan()
+    }
+
+    /// ### In world units
+    pub fn aperture_radius(&self) -> f64 {
+        0.5 * self.focal_length / self.f_number * self.unit
+    }
+}
+
+/// Shape the lens is sampled on, within the unit disk.
+#[derive(Debug, Clone)]
+pub enum Aperture {
+    Disk,
+    Polygon {
+        /// Corners on the unit circle.
+        corners: Vec<vec2>,
+    },
+    /// Pixels picked in proportion to their brightness.
+    Image {
+        /// Running sum of the pixel weights, for inversion.
+        cdf: Arc<Vec<f64>>,
+        width: u32,
+        height: u32,
+    },
+}
+
+impl Aperture {
+    /// ## PANICS if the aperture image cannot be read or is black.
+    pub fn new(lens: Option<&Lens>) -> Self {
+        let Some(lens) = lens else {
+            return Aperture::Disk;
+        };
+
+        if let Some(path) = &lens.aperture_image {
+            let img = image::open(path)
+                .unwrap_or_else(|_| panic!("Aperture image not found: {}", path))
+                .to_luma32f();
+            let mut total = 0.0;
+            let cdf: Vec<f64> = img
+                .pixels()
+                .map(|px| {
+                    total += px[0] as f64;
+                    total
+                })
+                .collect();
+            if total <= 0.0 {
+                panic!("Aperture image is black: {}", path);
+            }
+            return Aperture::Image {
+                cdf: Arc::new(cdf),
+                width: img.width(),
+                height: img.height(),
+            };
+        }
+
+        if lens.blades >= 3 {
+            let rotation = deg2rad(lens.blade_rotation);
+            let corners = (0..lens.blades)
+                .map(|i| rotation + TAU * i as f64 / lens.blades as f64)
+                .map(|angle| vec2::new(angle.cos(), angle.sin()))
+                .collect();
+            return Aperture::Polygon { corners };
+        }
+
+        Aperture::Disk
+    }
+
+    /// Uniform within the shape.
+    pub fn sample(&self, rng: &mut ThreadRng) -> vec2 {
+        match self {
+            Aperture::Disk => sample_on_disk(rng),
+            Aperture::Polygon { corners } => {
+                // triangles from the center all have the same area
+                let i = ((sample_uniform_01(rng) * corners.len() as f64) as usize)
+                    .min(corners.len() - 1);
+                let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+                let (mut s, mut t) = (sample_uniform_01(rng), sample_uniform_01(rng));
+                if s + t > 1.0 {
+                    (s, t) = (1.0 - s, 1.0 - t);
+                }
+                s * a + t * b
+            }
+            Aperture::Image { cdf, width, height } => {
+                let target = sample_uniform_01(rng) * cdf.last().unwrap();
+                let i = cdf.partition_point(|&sum| sum <= target).min(cdf.len() - 1);
+                let (ix, iy) = (i as u32 % width, i as u32 / width);
+
+                // the longer side spans the unit disk
+                let scale = 2.0 / *width.max(height) as f64;
+                vec2::new(
+                    (ix as f64 + sample_uniform_01(rng) - 0.5 * *width as f64) * scale,
+                    (0.5 * *height as f64 - iy as f64 - sample_uniform_01(rng)) * scale,
+                )
+            }
+        }
+    }
+}
@@ -1,6 +1,13 @@
 pub mod camera;
 pub mod camera_lens;
+pub mod distortion;
+pub mod exposure;
 pub mod image_space;
+pub mod lens;
+pub mod path;
+pub mod projection;
+pub mod stereo;
+pub mod tilt_shift;
 
 #[cfg(test)]
 pub mod tests {
@@ -8,9 +15,12 @@ pub mod tests {
 
     use glm::UVec2;
 
-    use crate::helpers::types::vec3;
+    use crate::helpers::types::{vec2, vec3};
 
-    use super::camera::CameraBuilder;
+    use super::{
+        camera::CameraBuilder, camera_lens::LensCameraBuilder, distortion::Distortion,
+        exposure::Exposure, tilt_shift::TiltShift,
+    };
 
     #[test]
     fn test_cam() {
@@ -31,4 +41,83 @@ pub mod tests {
         dbg!(diff);
         assert!(diff < 1e-4);
     }
+
+    #[test]
+    fn test_undistort_inverts_distort() {
+        let distortion = Distortion {
+            k1: -0.25,
+            k2: 0.05,
+            k3: 0.01,
+            p1: 0.01,
+            p2: -0.005,
+        };
+        for p in [
+            vec2::new(0.3, -0.2),
+            vec2::new(-0.6, 0.4),
+            vec2::new(0.0, 0.5),
+        ] {
+            let back = distortion.undistort(distortion.distort(p));
+            assert!((back - p).magnitude() < 1e-6, "{:?} {:?}", p, back);
+        }
+    }
+
+    #[test]
+    fn test_distortion_folding_over() {
+        let barrel = Distortion {
+            k1: -1.0,
+            ..Default::default()
+        };
+        assert!(barrel.validate(0.5).is_ok());
+        assert!(barrel.validate(1.5).is_err());
+        let back = barrel.undistort(vec2::new(1.5, 0.5));
+        assert!(back.x.is_finite() && back.y.is_finite());
+    }
+
+    #[test]
+    fn test_tilted_rays_point_forward() {
+        let lookat = vec3::new(0.0, 0.0, -1.0);
+        for tilt in [vec2::new(45.0, 0.0), vec2::new(80.0, 30.0)] {
+            let cam = LensCameraBuilder {
+                defocus_angle: 0.0,
+                resolution: UVec2::new(16, 16),
+                yfov: TAU / 4.0,
+                viewport_distance: 1.0,
+                pos: vec3::zeros(),
+                lookat,
+                up: vec3::new(0.0, 1.0, 0.0),
+                shutter: vec2::zeros(),
+                path: None,
+                projection: Default::default(),
+                stereo: None,
+                lens: None,
+                distortion: None,
+                tilt_shift: Some(TiltShift {
+                    tilt,
+                    shift: vec2::zeros(),
+                }),
+                exposure: None,
+            }
+            .build();
+            let mut rng = rand::thread_rng();
+            for (ix, iy) in [(0, 0), (15, 0), (0, 15), (15, 15)] {
+                let ray = cam.sample_ray(ix, iy, 0.0, 0.0, &mut rng).unwrap();
+                assert!(ray.orig.iter().chain(ray.dir.iter()).all(|c| c.is_finite()));
+                assert!(ray.dir.dot(&lookat) > 0.0, "{:?} {:?}", tilt, ray.dir);
+            }
+        }
+    }
+
+    #[test]
+    fn test_exposure_stops() {
+        let mut exposure: Exposure = toml::from_str("").unwrap();
+        assert!((exposure.scale(1.0, || 0.0) - 1.0).abs() < 1e-12);
+        assert!((exposure.scale(2.0, || 0.0) - 0.25).abs() < 1e-12);
+
+        // two stops of ISO make up for two stops of aperture
+        exposure.iso = 400.0;
+        assert!((exposure.scale(2.0, || 0.0) - 1.0).abs() < 1e-12);
+
+        exposure.auto = true;
+        assert!((exposure.scale(2.0, || 0.09) - 2.0).abs() < 1e-12);
+    }
 }
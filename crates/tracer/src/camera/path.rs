@@ -0,0 +1,17 @@
+use crate::{helpers::types::vec3, math::keyframes::Track};
+use serde::{Deserialize, Serialize};
+
+/// Camera parameters following tracks over animation time, each a keyframe list or a
+/// spline (see `TrackConfig`). Parameters without a track keep their static value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CameraPath {
+    pub pos: Option<Track<vec3>>,
+    /// Direction looked at.
+    pub lookat: Option<Track<vec3>>,
+    /// Point looked at, takes over `lookat`.
+    pub target: Option<Track<vec3>>,
+    /// ### In degrees
+    pub yfov: Option<Track<f64>>,
+    /// Distance to the plane in focus, which is also the viewport distance.
+    pub focus_distance: Option<Track<f64>>,
+}
@@ -0,0 +1,97 @@
+use crate::{helpers::types::vec3, math::angles::deg2rad};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{PI, TAU};
+
+/// How the image maps onto rays, set by the `[projection]` table of a camera.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Projection {
+    /// Rays spread from the lens through the viewport, `yfov` wide.
+    #[default]
+    Perspective,
+    /// Parallel rays along `lookat`, from an image `width` wide in world units
+    /// centered on the camera position. `yfov` and the lens are ignored.
+    Orthographic { width: f64 },
+    /// Every direction around the camera, longitude along x and latitude along y,
+    /// `lookat` at the center. Meant for 2:1 images.
+    Equirectangular,
+    /// A circle as wide as the shorter side of the image, `fov` across, up to 360.
+    /// Pixels outside of it stay black.
+    Fisheye {
+        /// ### In degrees
+        fov: f64,
+        #[serde(default)]
+        mapping: FisheyeMapping,
+    },
+    /// Six 90 degree faces in a 3 x 2 grid:
+    /// right, left, up on top, then down, front, back. Meant for 3:2 images.
+    Cubemap,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum FisheyeMapping {
+    /// Distance from the center proportional to the angle from `lookat`.
+    #[default]
+    Equidistant,
+    /// Keeps areas, like most real fisheye lenses.
+    Equisolid,
+}
+
+// Directions are in camera space: x to the right, y up, z forward.
+// `u`, `v` in \[0, 1\] from the left top corner of the image.
+
+pub fn equirectangular(u: f64, v: f64) -> vec3 {
+    let longitude = (u - 0.5) * TAU;
+    let latitude = (0.5 - v) * PI;
+    vec3::new(
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+        latitude.cos() * longitude.cos(),
+    )
+}
+
+/// `None` outside of the image circle.
+/// - `aspect`: width over height of the image.
+pub fn fisheye(u: f64, v: f64, aspect: f64, fov: f64, mapping: FisheyeMapping) -> Option<vec3> {
+    // unit radius on the shorter side
+    let (x, y) = if aspect >= 1.0 {
+        ((2.0 * u - 1.0) * aspect, 1.0 - 2.0 * v)
+    } else {
+        (2.0 * u - 1.0, (1.0 - 2.0 * v) / aspect)
+    };
+    let r = x.hypot(y);
+    if r > 1.0 {
+        return None;
+    }
+
+    let half_fov = 0.5 * deg2rad(fov).min(TAU);
+    let theta = match mapping {
+        FisheyeMapping::Equidistant => r * half_fov,
+        FisheyeMapping::Equisolid => 2.0 * (r * (0.5 * half_fov).sin()).asin(),
+    };
+    let phi = y.atan2(x);
+    Some(vec3::new(
+        theta.sin() * phi.cos(),
+        theta.sin() * phi.sin(),
+        theta.cos(),
+    ))
+}
+
+pub fn cubemap(u: f64, v: f64) -> vec3 {
+    let (col, row) = ((u * 3.0).min(2.0) as usize, (v * 2.0).min(1.0) as usize);
+    // position within the face, in \[-1, 1\]
+    let a = 2.0 * (u * 3.0 - col as f64) - 1.0;
+    let b = 1.0 - 2.0 * (v * 2.0 - row as f64);
+
+    let (x, y, z) = (vec3::x(), vec3::y(), vec3::z());
+    // (center, right, up) of each face
+    let (center, right, up) = match (row, col) {
+        (0, 0) => (x, -z, y),
+        (0, 1) => (-x, z, y),
+        (0, 2) => (y, x, -z),
+        (1, 0) => (-y, x, z),
+        (1, 1) => (z, x, y),
+        _ => (-z, -x, y),
+    };
+    center + a * right + b * up
+}
@@ -0,0 +1,58 @@
+use glm::UVec2;
+use serde::{Deserialize, Serialize};
+
+/// Both eyes rendered into one image, set by the `[stereo]` table of a camera.
+/// Perspective eyes look in parallel with their images shifted to converge,
+/// panoramas put their eyes on a circle (omni-directional stereo).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stereo {
+    #[serde(default)]
+    pub layout: StereoLayout,
+    /// Interpupillary distance, in world units.
+    #[serde(default = "default_ipd")]
+    pub ipd: f64,
+    /// Distance at which both eyes see the same point, at infinity if not set.
+    pub convergence: Option<f64>,
+}
+
+fn default_ipd() -> f64 {
+    0.064
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum StereoLayout {
+    /// Left eye on the left half.
+    #[default]
+    SideBySide,
+    /// Left eye on the top half.
+    OverUnder,
+}
+
+impl Stereo {
+    /// Resolution of each eye within an image of `resolution`.
+    pub fn eye_resolution(&self, resolution: UVec2) -> UVec2 {
+        match self.layout {
+            StereoLayout::SideBySide => UVec2::new(resolution.x / 2, resolution.y),
+            StereoLayout::OverUnder => UVec2::new(resolution.x, resolution.y / 2),
+        }
+    }
+
+    /// Side of the eye seeing pixel `(ix, iy)`, -1 for left and 1 for right,
+    /// with the pixel in the image of that eye.
+    pub fn eye_of(&self, ix: u32, iy: u32, eye_resolution: UVec2) -> (f64, u32, u32) {
+        match self.layout {
+            StereoLayout::SideBySide if ix >= eye_resolution.x => {
+                (1.0, ix - eye_resolution.x, iy)
+            }
+            StereoLayout::OverUnder if iy >= eye_resolution.y => {
+                (1.0, ix, iy - eye_resolution.y)
+            }
+            _ => (-1.0, ix, iy),
+        }
+    }
+
+    /// Shift of the eye, along the right of the camera for a perspective.
+    pub fn eye_offset(&self, side: f64) -> f64 {
+        0.5 * self.ipd * side
+    }
+}
@@ -0,0 +1,14 @@
+use crate::helpers::types::vec2;
+use serde::{Deserialize, Serialize};
+
+/// Tilt-shift lens, set by the `[tilt_shift]` table of a camera.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TiltShift {
+    /// Turns of the plane in focus around the right and the up of the camera.
+    /// ### In degrees
+    pub tilt: vec2,
+    /// Slides the image within the viewport, in image widths and heights,
+    /// e.g. to keep verticals straight while looking up.
+    pub shift: vec2,
+}
@@ -4,4 +4,14 @@ use thiserror::Error;
 pub enum SerdeError {
     #[error("Require (field: {}, type: {})", field, ty)]
     RequireFieldType { field: String, ty: String },
+    #[error("Invalid {}: {}", field, reason)]
+    Invalid { field: String, reason: String },
+}
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("Unsupported: {}", feature)]
+    Unsupported { feature: String },
+    #[error("Missing {}", what)]
+    Missing { what: String },
 }
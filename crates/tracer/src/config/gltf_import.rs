@@ -0,0 +1,352 @@
+use super::errors::ImportError;
+use crate::{
+    entity::{
+        analytic::{disk::Disk, sphere::Sphere},
+        instance::Instance,
+        mesh::{data::MeshData, triangle_mesh::TriangleMesh},
+        traits::Entity,
+    },
+    helpers::types::{color, mat4, vec2, vec3},
+    materials::material::{Material, MaterialKind, TextureMap},
+    math::{panics::PanickingNormalize, transform::Transform},
+};
+use gltf::{camera::Projection, image::Format, khr_lights_punctual::Kind, Node};
+use image::{imageops::FilterType, DynamicImage, RgbImage, RgbaImage};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    f64::consts::PI,
+    sync::Arc,
+};
+
+/// Side of the square textures are resampled to, as for `Texture` materials.
+const TEXTURE_RESOLUTION: u32 = 1024;
+/// Directional lights are disks this far away.
+const SUN_DISTANCE: f64 = 1000.0;
+/// Angular radius of directional lights, in radians.
+const SUN_ANGULAR_RADIUS: f64 = 0.02;
+
+/// A glTF 2.0 (`.gltf` or `.glb`) file, with its buffers and images loaded.
+pub struct GltfScene {
+    pub path: String,
+    document: gltf::Document,
+    buffers: Vec<gltf::buffer::Data>,
+    images: Vec<gltf::image::Data>,
+}
+
+/// Pose and field of view of a glTF camera, which looks along its local -z with +y up.
+pub struct GltfCamera {
+    pub pos: vec3,
+    pub lookat: vec3,
+    pub up: vec3,
+    /// In radians.
+    pub yfov: f64,
+}
+
+impl GltfScene {
+    pub fn open(path: &str) -> anyhow::Result<Self> {
+        let (document, buffers, images) = gltf::import(path)?;
+        Ok(Self {
+            path: path.to_string(),
+            document,
+            buffers,
+            images,
+        })
+    }
+
+    /// Meshes and punctual lights of the default scene, in world space.
+    /// - `mat_override`: replaces every glTF material.
+    /// - `light_radius`: radius of the spheres standing in for point and spot lights.
+    pub fn entities(
+        &self,
+        mat_override: Option<&Material>,
+        light_radius: f64,
+    ) -> anyhow::Result<Vec<Arc<dyn Entity>>> {
+        let materials: Vec<Material> = match mat_override {
+            Some(mat) => vec![mat.clone(); self.document.materials().len()],
+            None => self
+                .document
+                .materials()
+                .map(|mat| self.material(&mat).map(Material::from))
+                .collect::<anyhow::Result<_>>()?,
+        };
+        let default_mat = mat_override.cloned().unwrap_or(
+            MaterialKind::Lambertian {
+                albedo: color::new(0.8, 0.8, 0.8),
+            }
+            .into(),
+        );
+
+        let mut meshes: HashMap<usize, Option<Arc<dyn Entity>>> = HashMap::new();
+        let mut out = Vec::new();
+
+        for (node, world) in self.world_nodes() {
+            if let Some(mesh) = node.mesh() {
+                let entity = match meshes.entry(mesh.index()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(self.mesh(&mesh, &materials, &default_mat)?)
+                    }
+                };
+                if let Some(entity) = entity {
+                    out.push(Arc::new(Instance::new(entity.clone(), world)) as Arc<dyn Entity>);
+                }
+            }
+
+            if let Some(light) = node.light() {
+                let [r, g, b] = light.color();
+                let light_color = color::new(r as f64, g as f64, b as f64);
+                let intensity = light.intensity() as f64;
+                let pos = world.point(vec3::zeros());
+
+                out.push(match light.kind() {
+                    // the cone of spot lights is ignored
+                    Kind::Point | Kind::Spot { .. } => {
+                        // radiance of a sphere with the same intensity in candela
+                        let radiance = intensity / (PI * light_radius * light_radius);
+                        Arc::new(Sphere::new(
+                            pos,
+                            light_radius,
+                            MaterialKind::DiffuseLight {
+                                color: light_color * radiance,
+                            }
+                            .into(),
+                        ))
+                    }
+                    Kind::Directional => {
+                        // a far disk giving the same illuminance in lux
+                        let dir = world.vector(-vec3::z()).p_normalize();
+                        let solid_angle = PI * SUN_ANGULAR_RADIUS * SUN_ANGULAR_RADIUS;
+                        Arc::new(Disk::new(
+                            pos - dir * SUN_DISTANCE,
+                            dir,
+                            SUN_DISTANCE * SUN_ANGULAR_RADIUS,
+                            MaterialKind::DiffuseLight {
+                                color: light_color * intensity / solid_angle,
+                            }
+                            .into(),
+                        ))
+                    }
+                });
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Camera `index` of the file, placed by the first node using it.
+    pub fn camera(&self, index: usize) -> anyhow::Result<GltfCamera> {
+        let (node, world) = self
+            .world_nodes()
+            .into_iter()
+            .find(|(node, _)| node.camera().is_some_and(|cam| cam.index() == index))
+            .ok_or_else(|| ImportError::Missing {
+                what: format!("camera {} in {}", index, self.path),
+            })?;
+
+        let yfov = match node.camera().unwrap().projection() {
+            Projection::Perspective(perspective) => perspective.yfov() as f64,
+            Projection::Orthographic(_) => {
+                return Err(ImportError::Unsupported {
+                    feature: "orthographic glTF cameras".into(),
+                }
+                .into())
+            }
+        };
+
+        Ok(GltfCamera {
+            pos: world.point(vec3::zeros()),
+            lookat: world.vector(-vec3::z()).p_normalize(),
+            up: world.vector(vec3::y()).p_normalize(),
+            yfov,
+        })
+    }
+
+    /// Every node of the default (or first) scene with its node-to-world transform.
+    /// Nodes that are not invertible, e.g. scaled to zero to hide them, are left out.
+    fn world_nodes(&self) -> Vec<(Node<'_>, Transform)> {
+        fn visit<'a>(node: Node<'a>, parent: Transform, out: &mut Vec<(Node<'a>, Transform)>) {
+            let m = node.transform().matrix();
+            // glTF matrices are column major
+            let Some(local) = Transform::try_new(mat4::from_fn(|r, c| m[c][r] as f64)) else {
+                // scaled to nothing, along with its children
+                return;
+            };
+            let world = local.then(&parent);
+            for child in node.children() {
+                visit(child, world, out);
+            }
+            out.push((node, world));
+        }
+
+        let mut out = Vec::new();
+        let scene = self
+            .document
+            .default_scene()
+            .or_else(|| self.document.scenes().next());
+        if let Some(scene) = scene {
+            for node in scene.nodes() {
+                visit(node, Transform::identity(), &mut out);
+            }
+        }
+        out
+    }
+
+    /// One `TriangleMesh` in object space, each triangle primitive being a face group.
+    /// `None` if the mesh has no triangle.
+    fn mesh(
+        &self,
+        mesh: &gltf::Mesh,
+        materials: &[Material],
+        default_mat: &Material,
+    ) -> anyhow::Result<Option<Arc<dyn Entity>>> {
+        let mut parts = Vec::new();
+        let mut part_materials = Vec::new();
+        let mut closed = true;
+
+        for primitive in mesh.primitives() {
+            // points, lines and strips are skipped
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                continue;
+            }
+
+            let reader = primitive.reader(|buffer| Some(&self.buffers[buffer.index()]));
+            let to_vec3 = |[x, y, z]: [f32; 3]| vec3::new(x as f64, y as f64, z as f64);
+
+            let positions: Vec<vec3> = match reader.read_positions() {
+                Some(positions) => positions.map(to_vec3).collect(),
+                None => continue,
+            };
+            let indices: Vec<u32> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..positions.len() as u32).collect(),
+            };
+            if indices.len() < 3 {
+                continue;
+            }
+
+            let data = MeshData {
+                normals: reader
+                    .read_normals()
+                    .map(|normals| normals.map(to_vec3).collect()),
+                colors: reader
+                    .read_colors(0)
+                    .map(|colors| colors.into_rgb_f32().map(to_vec3).collect()),
+                uvs: reader.read_tex_coords(0).map(|uvs| {
+                    uvs.into_f32()
+                        .map(|[u, v]| vec2::new(u as f64, v as f64))
+                        .collect()
+                }),
+                indices: indices
+                    .chunks_exact(3)
+                    .map(|tri| [tri[0], tri[1], tri[2]])
+                    .collect(),
+                groups: None,
+                positions,
+            };
+
+            parts.push(data);
+            part_materials.push(match primitive.material().index() {
+                Some(index) => materials[index].clone(),
+                None => default_mat.clone(),
+            });
+            // single-sided glTF surfaces are hit from behind as leaving a solid,
+            // which dielectrics need and opaque materials treat as culled
+            closed &= !primitive.material().double_sided();
+        }
+
+        if parts.is_empty() {
+            return Ok(None);
+        }
+        let data = MeshData::merged(parts);
+        Ok(Some(Arc::new(TriangleMesh::new(
+            Arc::new(data),
+            part_materials,
+            closed,
+        ))))
+    }
+
+    /// Maps a metallic-roughness material onto the closest native material.
+    /// Metallic-roughness and other textures besides the base color are ignored.
+    fn material(&self, mat: &gltf::Material) -> anyhow::Result<MaterialKind> {
+        let emission = mat.emissive_factor().map(|x| x as f64);
+        let strength = mat.emissive_strength().unwrap_or(1.0) as f64;
+        if emission.iter().any(|&x| x > 0.0) {
+            return Ok(MaterialKind::DiffuseLight {
+                color: color::from(emission) * strength,
+            });
+        }
+
+        if mat
+            .transmission()
+            .is_some_and(|transmission| transmission.transmission_factor() > 0.5)
+        {
+            return Ok(MaterialKind::Dielectric {
+                eta: mat.ior().unwrap_or(1.5) as f64,
+            });
+        }
+
+        let pbr = mat.pbr_metallic_roughness();
+        let [r, g, b, _] = pbr.base_color_factor();
+        let albedo = color::new(r as f64, g as f64, b as f64);
+        let roughness = pbr.roughness_factor() as f64;
+
+        if pbr.metallic_factor() >= 0.5 {
+            return Ok(if roughness < 0.05 {
+                MaterialKind::Metal { albedo }
+            } else {
+                MaterialKind::FuzzedMetal {
+                    albedo,
+                    fuzz: roughness.min(1.0),
+                }
+            });
+        }
+
+        match pbr.base_color_texture() {
+            Some(info) => {
+                let image = &self.images[info.texture().source().index()];
+                let mut map = self
+                    .dynamic_image(image)?
+                    .resize_exact(TEXTURE_RESOLUTION, TEXTURE_RESOLUTION, FilterType::Gaussian)
+                    .into_rgb32f();
+                // the base color factor scales the texture
+                for px in map.pixels_mut() {
+                    px[0] *= r;
+                    px[1] *= g;
+                    px[2] *= b;
+                }
+                Ok(MaterialKind::Texture {
+                    map: Arc::new(TextureMap {
+                        resolution: TEXTURE_RESOLUTION,
+                        path: format!("{}#image{}", self.path, info.texture().source().index()),
+                        map,
+                    }),
+                })
+            }
+            None => Ok(MaterialKind::Lambertian { albedo }),
+        }
+    }
+
+    fn dynamic_image(&self, image: &gltf::image::Data) -> anyhow::Result<DynamicImage> {
+        let (w, h) = (image.width, image.height);
+        let img = match image.format {
+            Format::R8G8B8 => {
+                RgbImage::from_raw(w, h, image.pixels.clone()).map(DynamicImage::from)
+            }
+            Format::R8G8B8A8 => {
+                RgbaImage::from_raw(w, h, image.pixels.clone()).map(DynamicImage::from)
+            }
+            format => {
+                return Err(ImportError::Unsupported {
+                    feature: format!("glTF image format {:?}", format),
+                }
+                .into())
+            }
+        };
+        img.ok_or_else(|| {
+            ImportError::Missing {
+                what: format!("pixels of an image in {}", self.path),
+            }
+            .into()
+        })
+    }
+}
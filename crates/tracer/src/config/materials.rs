@@ -5,7 +5,7 @@ use toml::Value;
 
 use crate::{
     helpers::types::color,
-    materials::material::{Material, TextureMap},
+    materials::material::{Material, MaterialKind, TextureMap},
 };
 
 use super::toml_common::{value_get_into, value_get_into_option};
@@ -33,7 +33,8 @@ impl From<Value> for MaterialMap {
 
         let map = mats
             .iter()
-            .map(|mat| {
+            .enumerate()
+            .map(|(i, mat)| {
                 let name = mat
                     .get("name")
                     .expect("Expect a name")
@@ -46,25 +47,25 @@ impl From<Value> for MaterialMap {
                     .as_str()
                     .expect("Expect mat type to be string");
 
-                let material = match mat_type {
-                    "Lambertian" => Material::Lambertian {
+                let kind = match mat_type {
+                    "Lambertian" => MaterialKind::Lambertian {
                         albedo: value_get_into(mat, "albedo"),
                     },
 
-                    "Metal" => Material::Metal {
+                    "Metal" => MaterialKind::Metal {
                         albedo: value_get_into(mat, "albedo"),
                     },
 
-                    "FuzzedMetal" => Material::FuzzedMetal {
+                    "FuzzedMetal" => MaterialKind::FuzzedMetal {
                         albedo: value_get_into(mat, "albedo"),
                         fuzz: value_get_into(mat, "fuzz"),
                     },
 
-                    "Dielectric" => Material::Dielectric {
+                    "Dielectric" => MaterialKind::Dielectric {
                         eta: value_get_into(mat, "eta"),
                     },
 
-                    "PolarChecker" => Material::PolarChecker {
+                    "PolarChecker" => MaterialKind::PolarChecker {
                         color1: value_get_into(mat, "color1"),
                         color2: value_get_into_option(mat, "color2")
                             .unwrap_or(color::new(1.0, 1.0, 1.0)),
@@ -81,7 +82,7 @@ impl From<Value> for MaterialMap {
                             .resize(resolution, resolution, FilterType::Gaussian)
                             .into_rgb32f();
 
-                        Material::Texture {
+                        MaterialKind::Texture {
                             map: Arc::new(TextureMap {
                                 resolution,
                                 path,
@@ -90,17 +91,21 @@ impl From<Value> for MaterialMap {
                         }
                     }
 
-                    "DiffuseLight" => Material::DiffuseLight {
+                    "DiffuseLight" => MaterialKind::DiffuseLight {
                         color: value_get_into(mat, "color"),
                     },
 
-                    "Smoke" => Material::Smoke {
+                    "Smoke" => MaterialKind::Smoke {
                         k: value_get_into(mat, "k"),
                     },
 
                     _ => panic!("Unsupported material type: {}", mat_type),
                 };
 
+                let material = Material {
+                    id: i as u32 + 1,
+                    kind,
+                };
                 (name.to_string(), material)
             })
             .collect();
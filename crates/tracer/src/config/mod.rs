@@ -1,4 +1,5 @@
 pub mod materials;
 pub mod scene;
 pub mod toml_common;
-pub mod errors;
\ No newline at end of file
+pub mod errors;
+pub mod gltf_import;
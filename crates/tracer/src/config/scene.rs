@@ -1,22 +1,38 @@
 use super::{
     errors::SerdeError,
+    gltf_import::GltfScene,
     materials::MaterialMap,
     toml_common::{value_get_into, value_get_into_option},
 };
 use crate::{
     entity::{
         analytic::{
-            box_::Box, parallelogram::Parallelogram, plane::Plane, smoke_sphere::SmokeSphere,
-            somke_box::SmokeBox, sphere::Sphere, triangle::Triangle,
+            box_::Box, capsule::Capsule, cone::Cone, cylinder::Cylinder, disk::Disk,
+            parallelogram::Parallelogram, plane::Plane, smoke_sphere::SmokeSphere,
+            somke_box::SmokeBox, sphere::Sphere, torus::Torus, triangle::Triangle,
+        },
+        animated::{
+            keyframed::{Keyframed, MaterialTracks},
+            physics::{PhysicsWorld, RigidSphere, StaticPlane},
+            plane::AnimatedPlane,
+            sphere::AnimatedSphere,
+            timed::Timed,
         },
-        animated::{plane::AnimatedPlane, sphere::AnimatedSphere},
         animated_scene::AnimatedScene,
+        csg::Csg,
+        group::Group,
+        heightfield::Heightfield,
+        instance::Instance,
+        mesh::{data::MeshData, triangle_mesh::TriangleMesh},
         scene::Scene,
-        traits::{AnimatedEntity, Entity},
+        sdf::shape::SdfShape,
+        traits::{AnimatedEntity, Entity, Solid},
     },
     helpers::types::vec3,
+    materials::material::Material,
+    math::{keyframes::Track, transform::Transform},
 };
-use std::{fs, sync::Arc};
+use std::{collections::BTreeMap, fs, sync::Arc};
 use toml::Value;
 
 impl Scene {
@@ -42,6 +58,8 @@ impl From<Value> for Scene {
             .unwrap();
 
         let material_map = MaterialMap::configured(material_map_path).unwrap();
+        let mut loader = EntityLoader::new(&material_map);
+        loader.register(&value);
 
         let ents = value
             .get("entities")
@@ -51,82 +69,555 @@ impl From<Value> for Scene {
 
         let background = value_get_into(&value, "background");
 
-        let entities = ents
-            .iter()
-            .map(|ent| {
-                let ent_type = ent
-                    .get("type")
-                    .expect("Expect a type")
+        let mut entities = Vec::new();
+        for (ent, trajectory) in ents.iter().zip(simulate(&value, ents)) {
+            match trajectory {
+                Some(trajectory) => entities.push(loader.body(ent, trajectory)),
+                None => loader.collect(ent, None, None, 0, &mut entities),
+            }
+        }
+
+        Self {
+            entities,
+            background,
+            atmosphere: value_get_into_option(&value, "atmosphere"),
+        }
+    }
+}
+
+/// Groups nested deeper than this are assumed to reference themselves.
+const MAX_GROUP_DEPTH: u32 = 64;
+
+struct EntityLoader<'a> {
+    material_map: &'a MaterialMap,
+    /// Named entities that are only rendered through `Instance`s.
+    prototypes: BTreeMap<String, Arc<dyn Entity>>,
+    /// Tables the prototypes are built from, rebuilt within groups overriding the material.
+    prototype_sources: BTreeMap<String, Value>,
+    /// Named groups that are only rendered when placed by a `Group` entity.
+    groups: BTreeMap<String, Value>,
+}
+
+impl<'a> EntityLoader<'a> {
+    fn new(material_map: &'a MaterialMap) -> Self {
+        Self {
+            material_map,
+            prototypes: BTreeMap::new(),
+            prototype_sources: BTreeMap::new(),
+            groups: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the `[[groups]]` and `[[prototypes]]` of the scene, groups first as
+    /// prototypes may be built from them.
+    fn register(&mut self, value: &Value) {
+        if let Some(groups) = value.get("groups") {
+            for group in groups.as_array().expect("Expected list [[groups]]") {
+                let name = group
+                    .get("name")
+                    .expect("Expect a group name")
                     .as_str()
-                    .expect("Expect entity type to be string");
+                    .expect("Expect group name to be string");
+
+                self.groups.insert(name.to_string(), group.clone());
+            }
+        }
 
-                let mat_name = ent
-                    .get("material")
-                    .expect("Expect a material name")
+        if let Some(protos) = value.get("prototypes") {
+            for proto in protos.as_array().expect("Expected list [[prototypes]]") {
+                let name = proto
+                    .get("name")
+                    .expect("Expect a prototype name")
                     .as_str()
-                    .expect("Expect material name to be string");
-
-                let mat = material_map
-                    .map
-                    .get(mat_name)
-                    .expect(&format!("Material not found: {}", mat_name));
-
-                #[rustfmt::skip]
-            let entity: Arc<dyn Entity> = match ent_type {
-                "Sphere" => Arc::new(Sphere::new(
-                    value_get_into(ent, "center"),
-                    value_get_into(ent, "radius"),
-                    mat.clone(),
-                )),
-                "Plane" => Arc::new(Plane::new(
-                    value_get_into(ent, "point"),
-                    value_get_into(ent, "normal"),
-                    mat.clone(),
-                )),
-                "Triangle" => Arc::new(Triangle::new(
-                    value_get_into(ent, "a"),
-                    value_get_into(ent, "b"),
-                    value_get_into(ent, "c"),
-                    mat.clone(),
-                )),
-                "Parallelogram" => Arc::new(Parallelogram::new(
-                    value_get_into(ent, "a"),
-                    value_get_into(ent, "b"),
-                    value_get_into(ent, "c"),
-                    mat.clone(),
-                )),
-                "Box" => {
-                    Arc::new(Box::new(value_get_into(ent, "a"),
-                    value_get_into(ent, "b"),
-                    value_get_into(ent, "c"),
-                    value_get_into(ent, "d"), mat.clone()))
+                    .expect("Expect prototype name to be string");
+
+                let entity = self.entity(proto, None);
+                self.prototypes.insert(name.to_string(), entity);
+                self.prototype_sources
+                    .insert(name.to_string(), proto.clone());
+            }
+        }
+    }
+
+    fn material(&self, ent: &Value) -> Material {
+        let mat_name = ent
+            .get("material")
+            .expect("Expect a material name")
+            .as_str()
+            .expect("Expect material name to be string");
+
+        self.material_map
+            .map
+            .get(mat_name)
+            .unwrap_or_else(|| panic!("Material not found: {}", mat_name))
+            .clone()
+    }
+
+    /// Builds a single entity; groups are hit as one `Group`.
+    /// - `mat_override`: material replacing the ones of the entity, if any.
+    fn entity(&self, ent: &Value, mat_override: Option<&Material>) -> Arc<dyn Entity> {
+        let mut entities = Vec::new();
+        self.collect(ent, None, mat_override, 0, &mut entities);
+        if entities.len() == 1 {
+            entities.pop().unwrap()
+        } else {
+            Arc::new(Group::new(entities))
+        }
+    }
+
+    /// Flattens `ent` into `out`.
+    /// - `parent`: transform of the enclosing groups, applied after the entity's own.
+    /// - `mat_override`: material of the outermost group that has one.
+    fn collect(
+        &self,
+        ent: &Value,
+        parent: Option<Transform>,
+        mat_override: Option<&Material>,
+        depth: u32,
+        out: &mut Vec<Arc<dyn Entity>>,
+    ) {
+        let transform = match (transform_of(ent), parent) {
+            (Some(own), Some(parent)) => Some(own.then(&parent)),
+            (own, parent) => own.or(parent),
+        };
+
+        let is_group = ent.get("type").and_then(Value::as_str) == Some("Group")
+            || ent.get("children").is_some();
+        if !is_group {
+            let entity = self.untransformed_entity(ent, mat_override);
+            // the entity's own transform is animated, only the groups' one stays fixed
+            let (entity, transform) = match ent.get("animation") {
+                Some(animation) => (keyframed(entity, ent, animation), parent),
+                None => (entity, transform),
+            };
+            out.push(match transform {
+                Some(transform) => Arc::new(Instance::new(entity, transform)),
+                None => entity,
+            });
+            return;
+        }
+
+        if depth >= MAX_GROUP_DEPTH {
+            panic!("Groups are nested too deep, does a group contain itself?");
+        }
+
+        let own_mat = ent.get("material").map(|_| self.material(ent));
+        let mat_override = mat_override.or(own_mat.as_ref());
+
+        if let Some(name) = ent.get("group") {
+            let name = name.as_str().expect("Expect group name to be string");
+            let group = self
+                .groups
+                .get(name)
+                .unwrap_or_else(|| panic!("Group not found: {}", name));
+            self.collect(group, transform, mat_override, depth + 1, out);
+        } else {
+            let children = ent
+                .get("children")
+                .expect("Expect a group to have [[children]] or a group name")
+                .as_array()
+                .expect("Expect children to be a list");
+            for child in children {
+                self.collect(child, transform, mat_override, depth + 1, out);
+            }
+        }
+    }
+
+    fn untransformed_entity(
+        &self,
+        ent: &Value,
+        mat_override: Option<&Material>,
+    ) -> Arc<dyn Entity> {
+        let ent_type = ent
+            .get("type")
+            .expect("Expect a type")
+            .as_str()
+            .expect("Expect entity type to be string");
+
+        if ent_type == "Instance" {
+            let name: String = value_get_into(ent, "prototype");
+            let prototype = self
+                .prototypes
+                .get(&name)
+                .unwrap_or_else(|| panic!("Prototype not found: {}", name));
+            // shared, unless a group overrides the material and needs a copy of its own
+            return match mat_override {
+                Some(_) => self.entity(&self.prototype_sources[&name], mat_override),
+                None => prototype.clone(),
+            };
+        }
+
+        if ent_type == "Csg" {
+            return self.solid(ent, mat_override);
+        }
+
+        if ent_type == "Mesh" {
+            return self.mesh(ent, mat_override);
+        }
+
+        if ent_type == "Gltf" {
+            let path: String = value_get_into(ent, "path");
+            let own_mat = ent.get("material").map(|_| self.material(ent));
+            let entities = GltfScene::open(&path)
+                .and_then(|gltf| {
+                    gltf.entities(
+                        mat_override.or(own_mat.as_ref()),
+                        value_get_into_option(ent, "light_radius").unwrap_or(0.1),
+                    )
+                })
+                .unwrap_or_else(|e| panic!("Cannot import glTF {}: {}", path, e));
+            return Arc::new(Group::new(entities));
+        }
+
+        let mat = match mat_override {
+            Some(mat) => mat.clone(),
+            None => self.material(ent),
+        };
+
+        #[rustfmt::skip]
+        let entity: Arc<dyn Entity> = match ent_type {
+            "Sphere" => Arc::new(Sphere::new(
+                value_get_into(ent, "center"),
+                value_get_into(ent, "radius"),
+                mat,
+            )),
+            "Plane" => Arc::new(Plane::new(
+                value_get_into(ent, "point"),
+                value_get_into(ent, "normal"),
+                mat,
+            )),
+            "Triangle" => Arc::new(Triangle::new(
+                value_get_into(ent, "a"),
+                value_get_into(ent, "b"),
+                value_get_into(ent, "c"),
+                mat,
+            )),
+            "Parallelogram" => Arc::new(Parallelogram::new(
+                value_get_into(ent, "a"),
+                value_get_into(ent, "b"),
+                value_get_into(ent, "c"),
+                mat,
+            )),
+            "Box" if ent.get("min").is_some() => Arc::new(Box::axis_aligned(
+                value_get_into(ent, "min"),
+                value_get_into(ent, "max"),
+                mat,
+            )),
+            "Box" => {
+                Arc::new(Box::new(value_get_into(ent, "a"),
+                value_get_into(ent, "b"),
+                value_get_into(ent, "c"),
+                value_get_into(ent, "d"), mat))
+            }
+            "Disk" => Arc::new(Disk::new(
+                value_get_into(ent, "center"),
+                value_get_into(ent, "normal"),
+                value_get_into(ent, "radius"),
+                mat,
+            )),
+            "Cylinder" => Arc::new(Cylinder::new(
+                value_get_into(ent, "base"),
+                value_get_into(ent, "top"),
+                value_get_into(ent, "radius"),
+                value_get_into_option(ent, "capped").unwrap_or(true),
+                mat,
+            )),
+            "Cone" => Arc::new(Cone::new(
+                value_get_into(ent, "base"),
+                value_get_into(ent, "apex"),
+                value_get_into(ent, "radius"),
+                value_get_into_option(ent, "capped").unwrap_or(true),
+                mat,
+            )),
+            "Torus" => Arc::new(Torus::new(
+                value_get_into(ent, "center"),
+                value_get_into(ent, "axis"),
+                value_get_into(ent, "major_radius"),
+                value_get_into(ent, "minor_radius"),
+                mat,
+            )),
+            "Capsule" => Arc::new(Capsule::new(
+                value_get_into(ent, "a"),
+                value_get_into(ent, "b"),
+                value_get_into(ent, "radius"),
+                mat,
+            )),
+            "Sdf" => {
+                let mut shape = SdfShape::new(
+                    value_get_into(ent, "sdf"),
+                    value_get_into_option(ent, "bounds"),
+                    mat,
+                );
+                if let Some(max_steps) = value_get_into_option(ent, "max_steps") {
+                    shape.max_steps = max_steps;
+                }
+                if let Some(epsilon) = value_get_into_option(ent, "epsilon") {
+                    shape.epsilon = epsilon;
                 }
-                "SmokeSphere" => Arc::new(SmokeSphere::new(
-                    value_get_into(ent, "center"),
-                    value_get_into(ent, "radius"),
-                    mat.clone(),
-                )),
-                "SmokeBox" => {
-                    Arc::new(SmokeBox::new(value_get_into(ent, "a"),
-                    value_get_into(ent, "b"),
-                    value_get_into(ent, "c"),
-                    value_get_into(ent, "d"), mat.clone()))
+                if let Some(step_scale) = value_get_into_option(ent, "step_scale") {
+                    shape.step_scale = step_scale;
                 }
+                Arc::new(shape)
+            }
+            "Heightfield" => Arc::new(Heightfield::from_image(
+                &value_get_into::<String>(ent, "path"),
+                value_get_into(ent, "min"),
+                value_get_into(ent, "size"),
+                mat,
+            )),
+            "SmokeSphere" => Arc::new(SmokeSphere::new(
+                value_get_into(ent, "center"),
+                value_get_into(ent, "radius"),
+                mat,
+            )),
+            "SmokeBox" => {
+                Arc::new(SmokeBox::new(value_get_into(ent, "a"),
+                value_get_into(ent, "b"),
+                value_get_into(ent, "c"),
+                value_get_into(ent, "d"), mat))
+            }
 
-                _ => panic!("Unsupported entity type"),
-            };
+            _ => panic!("Unsupported entity type"),
+        };
 
-                entity
-            })
-            .collect();
+        entity
+    }
 
-        Self {
-            entities,
-            background,
+    /// A sphere following the `trajectory` of its center.
+    fn body(&self, ent: &Value, trajectory: Track<vec3>) -> Arc<dyn Entity> {
+        let sphere = Sphere::new(
+            vec3::zeros(),
+            value_get_into(ent, "radius"),
+            self.material(ent),
+        );
+        let mut keyframed = Keyframed::new(Arc::new(sphere));
+        keyframed.translate_track = Some(trajectory);
+        Arc::new(keyframed)
+    }
+
+    /// A mesh read from `path`, or given inline by `vertices` and `indices`.
+    /// Either `material` for the whole mesh, or `materials` picked by the face `groups`.
+    fn mesh(&self, ent: &Value, mat_override: Option<&Material>) -> Arc<dyn Entity> {
+        let data = match value_get_into_option::<String>(ent, "path") {
+            Some(path) => MeshData::load(&path)
+                .unwrap_or_else(|e| panic!("Cannot load mesh {}: {}", path, e)),
+            None => MeshData {
+                positions: value_get_into(ent, "vertices"),
+                normals: value_get_into_option(ent, "normals"),
+                colors: value_get_into_option(ent, "colors"),
+                uvs: value_get_into_option(ent, "uvs"),
+                indices: value_get_into(ent, "indices"),
+                groups: None,
+            },
+        };
+        let data = MeshData {
+            groups: value_get_into_option(ent, "groups").or(data.groups),
+            ..data
+        };
+
+        let materials = match (
+            mat_override,
+            value_get_into_option::<Vec<String>>(ent, "materials"),
+        ) {
+            // every face group takes the override
+            (Some(mat), _) => {
+                let n_groups = data
+                    .groups
+                    .as_ref()
+                    .and_then(|groups| groups.iter().max())
+                    .map_or(1, |&max| max as usize + 1);
+                vec![mat.clone(); n_groups]
+            }
+            (None, Some(names)) => names
+                .iter()
+                .map(|name| {
+                    self.material_map
+                        .map
+                        .get(name)
+                        .unwrap_or_else(|| panic!("Material not found: {}", name))
+                        .clone()
+                })
+                .collect(),
+            (None, None) => vec![self.material(ent)],
+        };
+
+        Arc::new(TriangleMesh::new(
+            Arc::new(data),
+            materials,
+            value_get_into_option(ent, "closed").unwrap_or(false),
+        ))
+    }
+
+    /// Builds an operand of a `Csg`; the material of a `Csg` overrides its operands'.
+    fn solid(&self, ent: &Value, mat_override: Option<&Material>) -> Arc<dyn Solid> {
+        let ent_type = ent
+            .get("type")
+            .expect("Expect a type")
+            .as_str()
+            .expect("Expect solid type to be string");
+
+        if ent_type == "Csg" {
+            let own_mat = ent.get("material").map(|_| self.material(ent));
+            let mat_override = mat_override.or(own_mat.as_ref());
+            return Arc::new(Csg::new(
+                value_get_into(ent, "op"),
+                self.solid(
+                    ent.get("left").expect("Expect a left operand"),
+                    mat_override,
+                ),
+                self.solid(
+                    ent.get("right").expect("Expect a right operand"),
+                    mat_override,
+                ),
+            ));
+        }
+
+        let mat = match mat_override {
+            Some(mat) => mat.clone(),
+            None => self.material(ent),
+        };
+
+        match ent_type {
+            "Sphere" => Arc::new(Sphere::new(
+                value_get_into(ent, "center"),
+                value_get_into(ent, "radius"),
+                mat,
+            )),
+            "Box" if ent.get("min").is_some() => Arc::new(Box::axis_aligned(
+                value_get_into(ent, "min"),
+                value_get_into(ent, "max"),
+                mat,
+            )),
+            "Box" => Arc::new(Box::new(
+                value_get_into(ent, "a"),
+                value_get_into(ent, "b"),
+                value_get_into(ent, "c"),
+                value_get_into(ent, "d"),
+                mat,
+            )),
+            // only watertight shapes are solids
+            "Cylinder" => Arc::new(Cylinder::new(
+                value_get_into(ent, "base"),
+                value_get_into(ent, "top"),
+                value_get_into(ent, "radius"),
+                true,
+                mat,
+            )),
+            "Cone" => Arc::new(Cone::new(
+                value_get_into(ent, "base"),
+                value_get_into(ent, "apex"),
+                value_get_into(ent, "radius"),
+                true,
+                mat,
+            )),
+            "Torus" => Arc::new(Torus::new(
+                value_get_into(ent, "center"),
+                value_get_into(ent, "axis"),
+                value_get_into(ent, "major_radius"),
+                value_get_into(ent, "minor_radius"),
+                mat,
+            )),
+            "Capsule" => Arc::new(Capsule::new(
+                value_get_into(ent, "a"),
+                value_get_into(ent, "b"),
+                value_get_into(ent, "radius"),
+                mat,
+            )),
+            _ => panic!("Unsupported solid type: {}", ent_type),
         }
     }
 }
 
+/// Reads the optional `translate`, `rotate` (euler angles in degrees) and `scale` fields.
+fn transform_of(ent: &Value) -> Option<Transform> {
+    let translate: Option<vec3> = value_get_into_option(ent, "translate");
+    let rotate: Option<vec3> = value_get_into_option(ent, "rotate");
+    let scale: Option<vec3> = value_get_into_option(ent, "scale");
+
+    if translate.is_none() && rotate.is_none() && scale.is_none() {
+        return None;
+    }
+
+    Some(Transform::from_trs(
+        translate.unwrap_or(vec3::zeros()),
+        rotate.unwrap_or(vec3::zeros()),
+        scale.unwrap_or(vec3::new(1.0, 1.0, 1.0)),
+    ))
+}
+
+/// Wraps `entity` in the keyframe tracks of the `animation` table, which may animate
+/// `translate`, `rotate` and `scale`, as well as the `albedo`, `fuzz`, `eta` and
+/// `emission` of the material in its `material` table.
+/// Tracks are keyframe lists or splines, see `TrackConfig`.
+/// Components without a track keep the static value of `ent`.
+fn keyframed(entity: Arc<dyn Entity>, ent: &Value, animation: &Value) -> Arc<dyn Entity> {
+    let mut keyframed = Keyframed::new(entity);
+    if let Some(translate) = value_get_into_option(ent, "translate") {
+        keyframed.translate = translate;
+    }
+    if let Some(rotate) = value_get_into_option(ent, "rotate") {
+        keyframed.rotate = rotate;
+    }
+    if let Some(scale) = value_get_into_option(ent, "scale") {
+        keyframed.scale = scale;
+    }
+
+    keyframed.translate_track = value_get_into_option(animation, "translate");
+    keyframed.rotate_track = value_get_into_option(animation, "rotate");
+    keyframed.scale_track = value_get_into_option(animation, "scale");
+
+    if let Some(material) = animation.get("material") {
+        keyframed.material_tracks = MaterialTracks {
+            albedo: value_get_into_option(material, "albedo"),
+            fuzz: value_get_into_option(material, "fuzz"),
+            eta: value_get_into_option(material, "eta"),
+            emission: value_get_into_option(material, "emission"),
+        };
+    }
+
+    Arc::new(keyframed)
+}
+
+/// Trajectories of the top-level spheres with a `body` table (`mass`, `velocity` and
+/// `restitution`), simulated by the `[physics]` table of the scene.
+/// Top-level planes are what they collide with.
+fn simulate(value: &Value, ents: &[Value]) -> Vec<Option<Track<vec3>>> {
+    let is_body = |ent: &Value| ent.get("body").is_some();
+    if !ents.iter().any(is_body) {
+        return vec![None; ents.len()];
+    }
+
+    let settings = value_get_into_option(value, "physics")
+        .expect("Expect a [physics] table for entities with a body");
+    let mut world = PhysicsWorld::new(settings);
+
+    for ent in ents {
+        let ent_type = ent.get("type").and_then(Value::as_str);
+        if let Some(body) = ent.get("body") {
+            if ent_type != Some("Sphere") {
+                panic!("Only spheres can have a body, got {:?}", ent_type);
+            }
+            world.bodies.push(RigidSphere {
+                center: value_get_into(ent, "center"),
+                radius: value_get_into(ent, "radius"),
+                mass: value_get_into_option(body, "mass").unwrap_or(1.0),
+                velocity: value_get_into_option(body, "velocity").unwrap_or(vec3::zeros()),
+                restitution: value_get_into_option(body, "restitution").unwrap_or(0.8),
+            });
+        } else if ent_type == Some("Plane") {
+            world.planes.push(StaticPlane {
+                point: value_get_into(ent, "point"),
+                normal: value_get_into(ent, "normal"),
+            });
+        }
+    }
+
+    let mut trajectories = world.simulate().into_iter();
+    ents.iter()
+        .map(|ent| is_body(ent).then(|| trajectories.next().unwrap()))
+        .collect()
+}
+
 // ################################################################
 // ########################### animated ###########################
 // ################################################################
@@ -164,51 +655,61 @@ impl From<(Value, u32)> for AnimatedScene {
 
         let background = value_get_into(&value, "background");
 
+        let mut loader = EntityLoader::new(&material_map);
+        loader.register(&value);
+
         let entities = ents
-            .into_iter()
-            .map(|ent| {
+            .iter()
+            .zip(simulate(&value, ents))
+            .map(|(ent, trajectory)| {
+                if let Some(trajectory) = trajectory {
+                    return Arc::new(Timed::new(loader.body(ent, trajectory)))
+                        as Arc<dyn AnimatedEntity>;
+                }
+
                 let ent_type = ent
                     .get("type")
                     .expect("Expect a type")
                     .as_str()
                     .expect("Expect entity type to be string");
 
-                let mat_name = ent
-                    .get("material")
-                    .expect("Expect a material name")
-                    .as_str()
-                    .expect("Expect material name to be string");
-
-                let mat = material_map
-                    .map
-                    .get(mat_name)
-                    .expect(&format!("Material not found: {}", mat_name));
+                // spheres and planes keep moving by their deltas, other entities
+                // follow their keyframe tracks through the steps
+                let legacy = matches!(ent_type, "Sphere" | "Plane")
+                    && ent.get("animation").is_none()
+                    && transform_of(ent).is_none();
+                if !legacy {
+                    return Arc::new(Timed::new(loader.entity(ent, None)))
+                        as Arc<dyn AnimatedEntity>;
+                }
 
+                let mat = loader.material(ent);
                 let entity: Arc<dyn AnimatedEntity> = match ent_type {
                     "Sphere" => Arc::new(AnimatedSphere::new(
                         Sphere::new(
                             value_get_into(ent, "center"),
                             value_get_into(ent, "radius"),
-                            mat.clone(),
+                            mat,
                         ),
                         value_get_into_option(ent, "delta").unwrap_or(vec3::zeros()),
                     )),
-                    "Plane" => {
+                    _ => {
                         let normal = value_get_into(ent, "normal");
                         Arc::new(AnimatedPlane::new(
-                            Plane::new(value_get_into(ent, "point"), normal, mat.clone()),
+                            Plane::new(value_get_into(ent, "point"), normal, mat),
                             value_get_into_option(ent, "delta_point").unwrap_or(vec3::zeros()),
                             value_get_into_option(ent, "new_normal").unwrap_or(normal),
                         ))
                     }
-
-                    _ => panic!("Unsupported entity type"),
                 };
 
                 entity
             })
             .collect();
 
-        Self::new(entities, background, n_step)
+        Self {
+            atmosphere: value_get_into_option(&value, "atmosphere"),
+            ..Self::new(entities, background, n_step)
+        }
     }
 }
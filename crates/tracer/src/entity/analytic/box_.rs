@@ -1,5 +1,17 @@
 use super::{commons::Point, parallelogram::Parallelogram};
-use crate::{entity::traits::Entity, helpers::types::vec3, materials::material::Material};
+use crate::{
+    entity::{
+        csg::{Crossing, Span},
+        traits::{Entity, Solid},
+    },
+    helpers::types::vec3,
+    materials::material::Material,
+    math::{aabb::Aabb, interval::Interval},
+    tracer::ray::{
+        hit::{Hit, Normal},
+        ray::Ray,
+    },
+};
 
 #[derive(Debug)]
 pub struct Box {
@@ -29,6 +41,18 @@ impl Box {
         ];
         Self { a, b, c, d, mat, faces }
     }
+
+    /// Box whose faces are perpendicular to the axes.
+    pub fn axis_aligned(min: vec3, max: vec3, mat: Material) -> Self {
+        let size = max - min;
+        Self::new(
+            min,
+            min + vec3::new(size.x, 0.0, 0.0),
+            min + vec3::new(0.0, 0.0, size.z),
+            min + vec3::new(0.0, size.y, 0.0),
+            mat,
+        )
+    }
 }
 
 impl Entity for Box {
@@ -50,4 +74,60 @@ impl Entity for Box {
         nearest_hit
     }
 
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(parallelepiped_box(self.a, self.b, self.c, self.d))
+    }
+}
+
+impl Solid for Box {
+    fn spans(&self, ray: Ray) -> Vec<Span> {
+        let hits: Vec<Hit> = self
+            .faces
+            .iter()
+            .filter_map(|face| face.hit_by(ray, Interval::Unbounded))
+            .collect();
+
+        let enter = hits.iter().min_by(|h1, h2| h1.t.total_cmp(&h2.t));
+        let exit = hits.iter().max_by(|h1, h2| h1.t.total_cmp(&h2.t));
+
+        match (enter, exit) {
+            (Some(enter), Some(exit)) if exit.t > enter.t => vec![Span {
+                enter: face_crossing(enter, -1.0),
+                exit: face_crossing(exit, 1.0),
+            }],
+            _ => vec![],
+        }
+    }
+}
+
+/// Bounding box of the parallelepiped spanned by `a` and its neighbours `b`, `c`, `d`.
+pub fn parallelepiped_box(a: vec3, b: vec3, c: vec3, d: vec3) -> Aabb {
+    let (ab, ac, ad) = (b - a, c - a, d - a);
+    Aabb::from_points([
+        a,
+        b,
+        c,
+        d,
+        a + ab + ac,
+        a + ab + ad,
+        a + ac + ad,
+        a + ab + ac + ad,
+    ])
+}
+
+/// `sign` is -1 when entering the box and 1 when leaving it.
+fn face_crossing(hit: &Hit, sign: f64) -> Crossing {
+    let normal = match hit.normal {
+        Normal::Outward(n) | Normal::Inward(n) => n,
+    };
+    Crossing {
+        t: hit.t,
+        normal: if normal.dot(&hit.in_dir) * sign >= 0.0 {
+            normal
+        } else {
+            -normal
+        },
+        material: hit.material,
+        material_id: hit.material_id,
+    }
 }
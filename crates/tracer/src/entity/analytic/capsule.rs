@@ -0,0 +1,98 @@
+use super::{
+    commons::{nearest_hit, spans_of, SurfacePoint},
+    disk::azimuth,
+};
+use crate::{
+    entity::{
+        csg::Span,
+        traits::{Entity, Solid},
+    },
+    helpers::types::{vec2, vec3},
+    materials::material::Material,
+    math::{
+        aabb::Aabb, frame::Frame, interval::Interval, panics::PanickingNormalize,
+        polynomial::solve_quadratic,
+    },
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+
+/// All points within `radius` of the segment from `a` to `b`.
+#[derive(Debug)]
+pub struct Capsule {
+    pub a: vec3,
+    pub b: vec3,
+    pub radius: f64,
+    pub mat: Material,
+
+    frame: Frame,
+    height: f64,
+}
+
+impl Capsule {
+    pub fn new(a: vec3, b: vec3, radius: f64, mat: Material) -> Self {
+        Self {
+            a,
+            b,
+            radius,
+            mat,
+            frame: Frame::new(a, b - a),
+            height: (b - a).p_magnitude(),
+        }
+    }
+
+    fn points(&self, ray: Ray) -> Vec<SurfacePoint> {
+        let o = self.frame.to_local(ray.orig);
+        let d = self.frame.vector_to_local(ray.dir);
+        let r2 = self.radius * self.radius;
+
+        // (t, whether the point belongs to this part of the surface)
+        let side = solve_quadratic(
+            d.x * d.x + d.y * d.y,
+            2.0 * (o.x * d.x + o.y * d.y),
+            o.x * o.x + o.y * o.y - r2,
+        )
+        .into_iter()
+        .filter(|t| (0.0..=self.height).contains(&(o.z + t * d.z)));
+
+        let cap = |z: f64| {
+            let oc = o - vec3::new(0.0, 0.0, z);
+            solve_quadratic(1.0, 2.0 * oc.dot(&d), oc.norm_squared() - r2)
+        };
+        let bottom = cap(0.0).into_iter().filter(|t| o.z + t * d.z < 0.0);
+        let top = cap(self.height)
+            .into_iter()
+            .filter(|t| o.z + t * d.z > self.height);
+
+        side.chain(bottom)
+            .chain(top)
+            .map(|t| {
+                let p = o + t * d;
+                let axis_point = vec3::new(0.0, 0.0, p.z.clamp(0.0, self.height));
+                SurfacePoint {
+                    t,
+                    normal: self.frame.vector_to_world((p - axis_point) / self.radius),
+                    uv: vec2::new(
+                        azimuth(p),
+                        (p.z + self.radius) / (self.height + 2.0 * self.radius),
+                    ),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Entity for Capsule {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        nearest_hit(ray, interval, &self.points(ray), &self.mat, true)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(Aabb::from_points([self.a, self.b]).padded(self.radius))
+    }
+}
+
+impl Solid for Capsule {
+    fn spans(&self, ray: Ray) -> Vec<Span> {
+        spans_of(&mut self.points(ray), &self.mat)
+    }
+}
@@ -1,4 +1,13 @@
-use crate::helpers::types::{vec2, vec3};
+use crate::{
+    entity::csg::{Crossing, Span},
+    helpers::types::{vec2, vec3},
+    materials::material::{FragMaterial, Material, MaterialKind},
+    math::interval::Interval,
+    tracer::ray::{
+        hit::{Hit, Normal},
+        ray::Ray,
+    },
+};
 use serde::{Deserialize, Serialize};
 
 fn default_uv() -> vec2 {
@@ -20,3 +29,124 @@ impl Point {
         }
     }
 }
+
+/// Where a ray's line meets a surface, before deciding which side it is hit from.
+#[derive(Debug, Clone, Copy)]
+pub struct SurfacePoint {
+    pub t: f64,
+    /// Points out of the body; any side will do for open surfaces.
+    pub normal: vec3,
+    pub uv: vec2,
+}
+
+/// Material at `uv`, textures and checkers are looked up by `uv`.
+pub fn uv_frag_material(mat: &Material, uv: vec2) -> FragMaterial {
+    match mat.clone().try_into() {
+        Ok(fmat) => fmat,
+        Err(_) => {
+            let u = uv.x.clamp(0.0, 1.0 - f64::EPSILON);
+            let v = uv.y.clamp(0.0, 1.0 - f64::EPSILON);
+            match &mat.kind {
+                MaterialKind::PolarChecker {
+                    color1,
+                    color2,
+                    ntheta,
+                    nphi,
+                } => {
+                    let colored =
+                        (u * *nphi as f64) as u32 % 2 == (v * *ntheta as f64) as u32 % 2;
+                    FragMaterial::Lambertian {
+                        albedo: if colored { *color1 } else { *color2 },
+                    }
+                }
+                MaterialKind::Texture { map } => FragMaterial::Lambertian {
+                    albedo: map.query(u, v),
+                },
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+/// Nearest point within `interval`.
+/// - `watertight`: the body is entered through an outward normal and left through an
+///   inward one. Otherwise the surface is two-sided and the normal always faces the ray.
+pub fn nearest_hit(
+    ray: Ray,
+    interval: Interval,
+    points: &[SurfacePoint],
+    mat: &Material,
+    watertight: bool,
+) -> Option<Hit> {
+    let nearest = points
+        .iter()
+        .filter(|p| interval.contains(p.t))
+        .min_by(|p1, p2| p1.t.total_cmp(&p2.t))?;
+
+    let facing = if nearest.normal.dot(&ray.dir) <= 0.0 {
+        nearest.normal
+    } else {
+        -nearest.normal
+    };
+    let normal = if watertight && nearest.normal.dot(&ray.dir) > 0.0 {
+        Normal::Inward(facing)
+    } else {
+        Normal::Outward(facing)
+    };
+
+    Some(Hit {
+        in_dir: ray.dir,
+        pos: ray.at(nearest.t),
+        material: uv_frag_material(mat, nearest.uv),
+        material_id: mat.id,
+        t: nearest.t,
+        normal,
+    })
+}
+
+/// Pairs up the points of a watertight surface into the spans inside it.
+pub fn spans_of(points: &mut [SurfacePoint], mat: &Material) -> Vec<Span> {
+    points.sort_by(|p1, p2| p1.t.total_cmp(&p2.t));
+
+    let crossing = |p: &SurfacePoint| Crossing {
+        t: p.t,
+        normal: p.normal,
+        material: uv_frag_material(mat, p.uv),
+        material_id: mat.id,
+    };
+
+    points
+        .chunks_exact(2)
+        .map(|pair| Span {
+            enter: crossing(&pair[0]),
+            exit: crossing(&pair[1]),
+        })
+        .collect()
+}
+
+/// Möller–Trumbore intersection of the whole line of `ray` with triangle `abc`.
+/// Returns `t` and the barycentric weights of `b` and `c`.
+pub fn ray_triangle(ray: &Ray, a: vec3, b: vec3, c: vec3) -> Option<(f64, f64, f64)> {
+    let ab = b - a;
+    let ac = c - a;
+    let p = ray.dir.cross(&ac);
+    let det = ab.dot(&p);
+    if det.abs() <= f64::EPSILON {
+        return None;
+    }
+
+    let inv_det = 1.0 / det;
+    let ao = ray.orig - a;
+    let u = ao.dot(&p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = ao.cross(&ab);
+    let v = ray.dir.dot(&q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    Some((ac.dot(&q) * inv_det, u, v))
+}
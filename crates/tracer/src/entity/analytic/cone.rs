@@ -0,0 +1,104 @@
+use super::{
+    commons::{nearest_hit, spans_of, SurfacePoint},
+    disk::{azimuth, cap_point, cap_uv, disk_box},
+};
+use crate::{
+    entity::{
+        csg::Span,
+        traits::{Entity, Solid},
+    },
+    helpers::types::{vec2, vec3},
+    materials::material::Material,
+    math::{
+        aabb::Aabb, frame::Frame, interval::Interval, panics::PanickingNormalize,
+        polynomial::solve_quadratic,
+    },
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+
+#[derive(Debug)]
+pub struct Cone {
+    pub base: vec3,
+    pub apex: vec3,
+    /// Radius of the base.
+    pub radius: f64,
+    /// Closed by a disk at the base, which makes the cone watertight.
+    pub capped: bool,
+    pub mat: Material,
+
+    frame: Frame,
+    height: f64,
+}
+
+impl Cone {
+    pub fn new(base: vec3, apex: vec3, radius: f64, capped: bool, mat: Material) -> Self {
+        Self {
+            base,
+            apex,
+            radius,
+            capped,
+            mat,
+            frame: Frame::new(base, apex - base),
+            height: (apex - base).p_magnitude(),
+        }
+    }
+
+    fn points(&self, ray: Ray) -> Vec<SurfacePoint> {
+        let o = self.frame.to_local(ray.orig);
+        let d = self.frame.vector_to_local(ray.dir);
+
+        // x^2 + y^2 = k^2 (h - z)^2
+        let k2 = (self.radius / self.height).powi(2);
+        let w = self.height - o.z;
+
+        let a = d.x * d.x + d.y * d.y - k2 * d.z * d.z;
+        let b = 2.0 * (o.x * d.x + o.y * d.y + k2 * w * d.z);
+        let c = o.x * o.x + o.y * o.y - k2 * w * w;
+
+        let mut points: Vec<_> = solve_quadratic(a, b, c)
+            .into_iter()
+            .filter_map(|t| {
+                let p = o + t * d;
+                if !(0.0..self.height).contains(&p.z) {
+                    // the apex has no normal
+                    return None;
+                }
+                let normal = vec3::new(p.x, p.y, k2 * (self.height - p.z));
+                Some(SurfacePoint {
+                    t,
+                    normal: self.frame.vector_to_world(normal).p_normalize(),
+                    uv: vec2::new(azimuth(p), p.z / self.height),
+                })
+            })
+            .collect();
+
+        if self.capped {
+            if let Some((t, p)) = cap_point(o, d, 0.0, self.radius) {
+                points.push(SurfacePoint {
+                    t,
+                    normal: -self.frame.w,
+                    uv: cap_uv(p, self.radius),
+                });
+            }
+        }
+
+        points
+    }
+}
+
+impl Entity for Cone {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        nearest_hit(ray, interval, &self.points(ray), &self.mat, self.capped)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let base = disk_box(self.base, self.frame.w, self.radius);
+        Some(base.union(&Aabb::new(self.apex, self.apex)))
+    }
+}
+
+impl Solid for Cone {
+    fn spans(&self, ray: Ray) -> Vec<Span> {
+        spans_of(&mut self.points(ray), &self.mat)
+    }
+}
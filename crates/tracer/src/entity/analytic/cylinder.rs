@@ -0,0 +1,103 @@
+use super::{
+    commons::{nearest_hit, spans_of, SurfacePoint},
+    disk::{azimuth, cap_point, cap_uv, disk_box},
+};
+use crate::{
+    entity::{
+        csg::Span,
+        traits::{Entity, Solid},
+    },
+    helpers::types::{vec2, vec3},
+    materials::material::Material,
+    math::{
+        aabb::Aabb, frame::Frame, interval::Interval, panics::PanickingNormalize,
+        polynomial::solve_quadratic,
+    },
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+
+#[derive(Debug)]
+pub struct Cylinder {
+    pub base: vec3,
+    pub top: vec3,
+    pub radius: f64,
+    /// Closed by disks at both ends, which makes the cylinder watertight.
+    pub capped: bool,
+    pub mat: Material,
+
+    frame: Frame,
+    height: f64,
+}
+
+impl Cylinder {
+    pub fn new(base: vec3, top: vec3, radius: f64, capped: bool, mat: Material) -> Self {
+        Self {
+            base,
+            top,
+            radius,
+            capped,
+            mat,
+            frame: Frame::new(base, top - base),
+            height: (top - base).p_magnitude(),
+        }
+    }
+
+    fn points(&self, ray: Ray) -> Vec<SurfacePoint> {
+        let o = self.frame.to_local(ray.orig);
+        let d = self.frame.vector_to_local(ray.dir);
+
+        let a = d.x * d.x + d.y * d.y;
+        let b = 2.0 * (o.x * d.x + o.y * d.y);
+        let c = o.x * o.x + o.y * o.y - self.radius * self.radius;
+
+        let mut points: Vec<_> = solve_quadratic(a, b, c)
+            .into_iter()
+            .filter_map(|t| {
+                let p = o + t * d;
+                (0.0..=self.height).contains(&p.z).then(|| SurfacePoint {
+                    t,
+                    normal: self
+                        .frame
+                        .vector_to_world(vec3::new(p.x, p.y, 0.0) / self.radius),
+                    uv: vec2::new(azimuth(p), p.z / self.height),
+                })
+            })
+            .collect();
+
+        if self.capped {
+            for (z, side) in [(0.0, -1.0), (self.height, 1.0)] {
+                if let Some((t, p)) = cap_point(o, d, z, self.radius) {
+                    points.push(SurfacePoint {
+                        t,
+                        normal: self.frame.w * side,
+                        uv: cap_uv(p, self.radius),
+                    });
+                }
+            }
+        }
+
+        points
+    }
+}
+
+impl Entity for Cylinder {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        nearest_hit(ray, interval, &self.points(ray), &self.mat, self.capped)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(
+            disk_box(self.base, self.frame.w, self.radius).union(&disk_box(
+                self.top,
+                self.frame.w,
+                self.radius,
+            )),
+        )
+    }
+}
+
+impl Solid for Cylinder {
+    fn spans(&self, ray: Ray) -> Vec<Span> {
+        spans_of(&mut self.points(ray), &self.mat)
+    }
+}
@@ -0,0 +1,83 @@
+use std::f64::consts::{PI, TAU};
+
+use super::commons::{nearest_hit, SurfacePoint};
+use crate::{
+    entity::traits::Entity,
+    helpers::types::{vec2, vec3},
+    materials::material::Material,
+    math::{aabb::Aabb, frame::Frame, interval::Interval, panics::PanickingFloatMethods},
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+
+#[derive(Debug)]
+pub struct Disk {
+    pub center: vec3,
+    pub normal: vec3,
+    pub radius: f64,
+    pub mat: Material,
+
+    frame: Frame,
+}
+
+impl Disk {
+    pub fn new(center: vec3, normal: vec3, radius: f64, mat: Material) -> Self {
+        let frame = Frame::new(center, normal);
+        Self {
+            center,
+            normal: frame.w,
+            radius,
+            mat,
+            frame,
+        }
+    }
+}
+
+impl Entity for Disk {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        let o = self.frame.to_local(ray.orig);
+        let d = self.frame.vector_to_local(ray.dir);
+
+        let point = cap_point(o, d, 0.0, self.radius).map(|(t, p)| SurfacePoint {
+            t,
+            normal: self.normal,
+            uv: cap_uv(p, self.radius),
+        });
+        nearest_hit(ray, interval, point.as_slice(), &self.mat, false)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(disk_box(self.center, self.normal, self.radius))
+    }
+}
+
+/// Where a local ray meets the disk of `radius` centered on the local z axis at height `z`.
+/// Returns `t` and the local hit position.
+pub fn cap_point(o: vec3, d: vec3, z: f64, radius: f64) -> Option<(f64, vec3)> {
+    if d.z.abs() <= f64::EPSILON {
+        return None;
+    }
+    let t = (z - o.z) / d.z;
+    let p = o + t * d;
+    if p.x * p.x + p.y * p.y <= radius * radius {
+        Some((t, p))
+    } else {
+        None
+    }
+}
+
+/// Angle around the local z axis, in \[0, 1\].
+pub fn azimuth(p: vec3) -> f64 {
+    (f64::atan2(p.y, p.x) + PI) / TAU
+}
+
+/// - u: angle around the center
+/// - v: distance to the center
+pub fn cap_uv(p: vec3, radius: f64) -> vec2 {
+    vec2::new(azimuth(p), (p.x * p.x + p.y * p.y).p_sqrt() / radius)
+}
+
+/// Bounding box of a disk with unit `normal`.
+pub fn disk_box(center: vec3, normal: vec3, radius: f64) -> Aabb {
+    let extent = normal.map(|n| radius * (1.0 - n * n).max(0.0).p_sqrt());
+    Aabb::new(center - extent, center + extent)
+}
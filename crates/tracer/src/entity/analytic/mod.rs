@@ -1,8 +1,13 @@
 pub mod box_;
+pub mod capsule;
 pub mod commons;
+pub mod cone;
+pub mod cylinder;
+pub mod disk;
 pub mod parallelogram;
 pub mod plane;
 pub mod smoke_sphere;
 pub mod somke_box;
 pub mod sphere;
+pub mod torus;
 pub mod triangle;
@@ -1,8 +1,8 @@
 use crate::{
     entity::traits::Entity,
     helpers::types::vec3,
-    materials::material::{FragMaterial, Material},
-    math::panics::PanickingNormalize,
+    materials::material::{FragMaterial, Material, MaterialKind},
+    math::{aabb::Aabb, panics::PanickingNormalize},
     tracer::ray::hit::{Hit, Normal},
 };
 
@@ -123,6 +123,7 @@ impl Entity for Parallelogram {
                 in_dir: ray.dir,
                 pos: ray.at(t),
                 material,
+                material_id: self.mat.id,
                 t,
                 normal: Normal::Outward(if v.dot(&self.normal) >= 0.0 {
                     self.normal
@@ -134,16 +135,26 @@ impl Entity for Parallelogram {
             None
         }
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let d = self.b.world + self.c.world - self.a.world;
+        Some(Aabb::from_points([
+            self.a.world,
+            self.b.world,
+            self.c.world,
+            d,
+        ]))
+    }
 }
 
 impl Parallelogram {
     fn frag_material(&self, k1: f64, k2: f64) -> FragMaterial {
         match self.mat.clone().try_into() {
             Ok(fmat) => fmat,
-            Err(_) => match &self.mat {
-                Material::PolarChecker { .. } => panic!("Unsupported material!"),
+            Err(_) => match &self.mat.kind {
+                MaterialKind::PolarChecker { .. } => panic!("Unsupported material!"),
 
-                Material::Texture { map } => {
+                MaterialKind::Texture { map } => {
                     let uv = self.a.uv + self.ab.uv * k1 + self.ac.uv * k2;
                     FragMaterial::Lambertian {
                         albedo: map.query(uv.x, uv.y),
@@ -42,6 +42,7 @@ impl Entity for Plane {
                     in_dir: ray.dir,
                     pos: ray.at(t),
                     material,
+                    material_id: self.mat.id,
                     t,
                     normal: Normal::Outward(if v.dot(&self.normal) >= 0.0 {
                         self.normal
@@ -1,8 +1,8 @@
 use crate::{
     entity::traits::Entity,
     helpers::types::vec3,
-    materials::material::{FragMaterial, Material},
-    math::distributions::sample_uniform_01,
+    materials::material::{FragMaterial, Material, MaterialKind},
+    math::{aabb::Aabb, distributions::sample_uniform_01},
     tracer::ray::hit::{Hit, Normal},
 };
 use rand::thread_rng;
@@ -18,7 +18,7 @@ pub struct SmokeSphere {
 
 impl SmokeSphere {
     pub fn new(center: vec3, radius: f64, mat: Material) -> Self {
-        let k = if let Material::Smoke { k } = mat {
+        let k = if let MaterialKind::Smoke { k } = mat.kind {
             k
         } else {
             panic!("Unsupported material!")
@@ -57,6 +57,7 @@ impl Entity for SmokeSphere {
                         in_dir: ray.dir,
                         pos: hitpos,
                         material: FragMaterial::Smoke,
+                        material_id: hit.material_id,
                         t,
                         normal: Normal::Outward(vec3::new(1.0, 0.0, 0.0)),
                     })
@@ -74,6 +75,10 @@ impl Entity for SmokeSphere {
             })
         }
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.sphere.bounding_box()
+    }
 }
 
 impl SmokeSphere {
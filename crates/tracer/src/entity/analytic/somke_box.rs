@@ -1,11 +1,11 @@
 use rand::thread_rng;
 
-use super::{commons::Point, parallelogram::Parallelogram};
+use super::{box_::parallelepiped_box, commons::Point, parallelogram::Parallelogram};
 use crate::{
     entity::traits::Entity,
     helpers::types::vec3,
-    materials::material::{FragMaterial, Material},
-    math::distributions::sample_uniform_01,
+    materials::material::{FragMaterial, Material, MaterialKind},
+    math::{aabb::Aabb, distributions::sample_uniform_01},
     tracer::ray::hit::{Hit, Normal},
 };
 
@@ -29,7 +29,7 @@ pub struct SmokeBox {
 impl SmokeBox {
     #[rustfmt::skip]
     pub fn new(a: vec3, b: vec3, c: vec3, d: vec3, mat: Material) -> Self {
-        let k = if let Material::Smoke { k } = mat { k } else {panic!("Unsupported material!")};
+        let k = if let MaterialKind::Smoke { k } = mat.kind { k } else {panic!("Unsupported material!")};
         let e = b + c - a;
         let f = b + d - a;
         let g = d + c - a;
@@ -80,6 +80,7 @@ impl Entity for SmokeBox {
                         in_dir: ray.dir,
                         pos: hitpos,
                         material: FragMaterial::Smoke,
+                        material_id: hit.material_id,
                         t,
                         normal: Normal::Outward(vec3::new(1.0, 0.0, 0.0)),
                     })
@@ -97,6 +98,10 @@ impl Entity for SmokeBox {
             })
         }
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(parallelepiped_box(self.a, self.b, self.c, self.d))
+    }
 }
 
 impl SmokeBox {
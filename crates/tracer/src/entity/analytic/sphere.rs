@@ -1,11 +1,20 @@
 use std::f64::consts::{PI, TAU};
 
 use crate::{
-    entity::traits::Entity,
+    entity::{
+        csg::{Crossing, Span},
+        traits::{Entity, Solid},
+    },
     helpers::types::vec3,
-    materials::material::{FragMaterial, Material},
-    math::panics::{PanickingFloatMethods, PanickingNormalize},
-    tracer::ray::hit::{Hit, Normal},
+    materials::material::{FragMaterial, Material, MaterialKind},
+    math::{
+        aabb::Aabb,
+        panics::{PanickingFloatMethods, PanickingNormalize},
+    },
+    tracer::ray::{
+        hit::{Hit, Normal},
+        ray::Ray,
+    },
 };
 
 #[derive(Debug)]
@@ -71,9 +80,44 @@ impl Entity for Sphere {
                 normal,
                 t,
                 material,
+                material_id: self.mat.id,
             })
         }
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r = vec3::new(self.radius, self.radius, self.radius);
+        Some(Aabb::new(self.center - r, self.center + r))
+    }
+}
+
+impl Solid for Sphere {
+    fn spans(&self, ray: Ray) -> Vec<Span> {
+        let o = ray.orig - self.center;
+        let half_b = o.dot(&ray.dir);
+        let c = o.norm_squared() - self.radius * self.radius;
+
+        let delta = half_b * half_b - c;
+        if delta <= 0.0 {
+            return vec![];
+        }
+
+        let sqrt_delta = delta.p_sqrt();
+        let crossing = |t: f64| {
+            let pos = ray.at(t);
+            Crossing {
+                t,
+                normal: (pos - self.center).p_normalize(),
+                material: self.frag_material(pos),
+                material_id: self.mat.id,
+            }
+        };
+
+        vec![Span {
+            enter: crossing(-half_b - sqrt_delta),
+            exit: crossing(-half_b + sqrt_delta),
+        }]
+    }
 }
 
 impl Sphere {
@@ -86,8 +130,8 @@ impl Sphere {
     fn frag_material(&self, hitpos: vec3) -> FragMaterial {
         match self.mat.clone().try_into() {
             Ok(fmat) => fmat,
-            Err(_) => match &self.mat {
-                Material::PolarChecker {
+            Err(_) => match &self.mat.kind {
+                MaterialKind::PolarChecker {
                     color1,
                     color2,
                     ntheta,
@@ -106,7 +150,7 @@ impl Sphere {
                     }
                 }
 
-                Material::Texture { map } => {
+                MaterialKind::Texture { map } => {
                     let (theta, phi) = self.spherical_coords(hitpos);
                     let x = phi / TAU;
                     let y = theta / PI;
@@ -0,0 +1,115 @@
+use std::f64::consts::{PI, TAU};
+
+use super::{
+    commons::{nearest_hit, spans_of, SurfacePoint},
+    disk::azimuth,
+};
+use crate::{
+    entity::{
+        csg::Span,
+        traits::{Entity, Solid},
+    },
+    helpers::types::{vec2, vec3},
+    materials::material::Material,
+    math::{
+        aabb::Aabb,
+        frame::Frame,
+        interval::Interval,
+        panics::{PanickingFloatMethods, PanickingNormalize},
+        polynomial::solve_quartic,
+    },
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+
+#[derive(Debug)]
+pub struct Torus {
+    pub center: vec3,
+    /// Normal of the plane the torus lies in.
+    pub axis: vec3,
+    /// Distance from the center to the middle of the tube.
+    pub major_radius: f64,
+    /// Radius of the tube.
+    pub minor_radius: f64,
+    pub mat: Material,
+
+    frame: Frame,
+}
+
+impl Torus {
+    pub fn new(
+        center: vec3,
+        axis: vec3,
+        major_radius: f64,
+        minor_radius: f64,
+        mat: Material,
+    ) -> Self {
+        let frame = Frame::new(center, axis);
+        Self {
+            center,
+            axis: frame.w,
+            major_radius,
+            minor_radius,
+            mat,
+            frame,
+        }
+    }
+
+    fn points(&self, ray: Ray) -> Vec<SurfacePoint> {
+        let d = self.frame.vector_to_local(ray.dir);
+        // start from the point nearest to the center to keep the quartic well conditioned
+        let o = self.frame.to_local(ray.orig);
+        let t0 = -o.dot(&d);
+        let o = o + t0 * d;
+
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + y^2)
+        let r2_major = self.major_radius * self.major_radius;
+        let r2_minor = self.minor_radius * self.minor_radius;
+        let e = o.norm_squared() - r2_major - r2_minor;
+        let f = o.dot(&d);
+        let four_r2 = 4.0 * r2_major;
+
+        let roots = solve_quartic(
+            1.0,
+            4.0 * f,
+            2.0 * e + 4.0 * f * f + four_r2 * d.z * d.z,
+            4.0 * f * e + 2.0 * four_r2 * o.z * d.z,
+            e * e - four_r2 * (r2_minor - o.z * o.z),
+        );
+
+        roots
+            .into_iter()
+            .map(|t| {
+                let p = o + t * d;
+                let s = p.norm_squared() - r2_major - r2_minor;
+                let normal = s * p + vec3::new(0.0, 0.0, 2.0 * r2_major * p.z);
+                let ring = (p.x * p.x + p.y * p.y).p_sqrt() - self.major_radius;
+                SurfacePoint {
+                    t: t + t0,
+                    normal: self.frame.vector_to_world(normal).p_normalize(),
+                    uv: vec2::new(azimuth(p), (f64::atan2(p.z, ring) + PI) / TAU),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Entity for Torus {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        nearest_hit(ray, interval, &self.points(ray), &self.mat, true)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let outer = self.major_radius + self.minor_radius;
+        let local = Aabb::new(
+            vec3::new(-outer, -outer, -self.minor_radius),
+            vec3::new(outer, outer, self.minor_radius),
+        );
+        Some(self.frame.aabb_to_world(&local))
+    }
+}
+
+impl Solid for Torus {
+    fn spans(&self, ray: Ray) -> Vec<Span> {
+        spans_of(&mut self.points(ray), &self.mat)
+    }
+}
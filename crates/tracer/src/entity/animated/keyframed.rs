@@ -0,0 +1,123 @@
+use crate::{
+    entity::{instance::hit_transformed, traits::Entity},
+    helpers::types::{color, vec3},
+    materials::material::FragMaterial,
+    math::{interval::Interval, keyframes::Track, transform::Transform},
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+use std::sync::Arc;
+
+/// Material parameters following tracks, each replacing the parameter of the same
+/// name wherever the hit material has one.
+#[derive(Debug, Clone, Default)]
+pub struct MaterialTracks {
+    /// Albedo of diffuse, metal and isotropic materials.
+    pub albedo: Option<Track<color>>,
+    pub fuzz: Option<Track<f64>>,
+    pub eta: Option<Track<f64>>,
+    /// Color of lights.
+    pub emission: Option<Track<color>>,
+}
+
+impl MaterialTracks {
+    pub fn is_empty(&self) -> bool {
+        self.albedo.is_none()
+            && self.fuzz.is_none()
+            && self.eta.is_none()
+            && self.emission.is_none()
+    }
+
+    pub fn apply(&self, mat: FragMaterial, time: f64) -> FragMaterial {
+        let albedo = |albedo| {
+            self.albedo
+                .as_ref()
+                .map_or(albedo, |track| track.sample(time))
+        };
+        match mat {
+            FragMaterial::Lambertian { albedo: a } => {
+                FragMaterial::Lambertian { albedo: albedo(a) }
+            }
+            FragMaterial::Metal { albedo: a } => FragMaterial::Metal { albedo: albedo(a) },
+            FragMaterial::FuzzedMetal { albedo: a, fuzz } => FragMaterial::FuzzedMetal {
+                albedo: albedo(a),
+                fuzz: self
+                    .fuzz
+                    .as_ref()
+                    .map_or(fuzz, |track| track.sample(time).clamp(0.0, 1.0)),
+            },
+            FragMaterial::Isotropic { albedo: a } => {
+                FragMaterial::Isotropic { albedo: albedo(a) }
+            }
+            FragMaterial::Dielectric { eta } => FragMaterial::Dielectric {
+                eta: self.eta.as_ref().map_or(eta, |track| track.sample(time)),
+            },
+            FragMaterial::DiffuseLight { color } => FragMaterial::DiffuseLight {
+                color: self
+                    .emission
+                    .as_ref()
+                    .map_or(color, |track| track.sample(time)),
+            },
+            other => other,
+        }
+    }
+}
+
+/// Any entity moved and restyled by keyframe tracks, evaluated at the time of each ray.
+///
+/// The transform is built like a static one: scale, then rotate (euler angles in
+/// degrees), then translate. Components without a track keep their static value.
+#[derive(Debug)]
+pub struct Keyframed {
+    pub entity: Arc<dyn Entity>,
+    pub translate: vec3,
+    pub rotate: vec3,
+    pub scale: vec3,
+    pub translate_track: Option<Track<vec3>>,
+    pub rotate_track: Option<Track<vec3>>,
+    pub scale_track: Option<Track<vec3>>,
+    pub material_tracks: MaterialTracks,
+}
+
+impl Keyframed {
+    pub fn new(entity: Arc<dyn Entity>) -> Self {
+        Self {
+            entity,
+            translate: vec3::zeros(),
+            rotate: vec3::zeros(),
+            scale: vec3::new(1.0, 1.0, 1.0),
+            translate_track: None,
+            rotate_track: None,
+            scale_track: None,
+            material_tracks: MaterialTracks::default(),
+        }
+    }
+
+    pub fn transform_at(&self, time: f64) -> Transform {
+        let sample = |track: &Option<Track<vec3>>, fixed: vec3| {
+            track.as_ref().map_or(fixed, |track| track.sample(time))
+        };
+        Transform::from_trs(
+            sample(&self.translate_track, self.translate),
+            sample(&self.rotate_track, self.rotate),
+            sample(&self.scale_track, self.scale),
+        )
+    }
+}
+
+impl Entity for Keyframed {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        let transform = self.transform_at(ray.time);
+        let hit = hit_transformed(self.entity.as_ref(), &transform, ray, interval)?;
+
+        if self.material_tracks.is_empty() {
+            Some(hit)
+        } else {
+            Some(Hit {
+                material: self.material_tracks.apply(hit.material, ray.time),
+                ..hit
+            })
+        }
+    }
+
+    // moves over time, so no bounding box
+}
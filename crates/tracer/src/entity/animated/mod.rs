@@ -1,2 +1,5 @@
+pub mod keyframed;
+pub mod physics;
 pub mod plane;
 pub mod sphere;
+pub mod timed;
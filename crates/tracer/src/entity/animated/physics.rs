@@ -0,0 +1,163 @@
+use crate::{
+    helpers::types::vec3,
+    math::{
+        keyframes::{Easing, Interpolation, Keyframe, Track},
+        panics::PanickingNormalize,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// A sphere moved by gravity and collisions.
+#[derive(Debug, Clone)]
+pub struct RigidSphere {
+    pub center: vec3,
+    pub radius: f64,
+    pub mass: f64,
+    pub velocity: vec3,
+    /// Share of the speed kept along the normal of a collision, 1 for perfect bounces.
+    pub restitution: f64,
+}
+
+/// A plane nothing passes through, bodies stay on the side its normal points to.
+#[derive(Debug, Clone)]
+pub struct StaticPlane {
+    pub point: vec3,
+    pub normal: vec3,
+}
+
+/// Settings of the `[physics]` table of a scene.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhysicsSettings {
+    #[serde(default = "default_gravity")]
+    pub gravity: vec3,
+    /// Animation time simulated, after which bodies stay where they are.
+    pub duration: f64,
+    /// Time of an integration step.
+    #[serde(default = "default_time_step")]
+    pub time_step: f64,
+}
+
+fn default_gravity() -> vec3 {
+    vec3::new(0.0, -9.81, 0.0)
+}
+
+fn default_time_step() -> f64 {
+    1e-3
+}
+
+/// Rigid spheres bouncing against planes and one another, simulated ahead of rendering
+/// so any animation time can be sampled, from frames as well as from rays.
+#[derive(Debug, Clone)]
+pub struct PhysicsWorld {
+    pub settings: PhysicsSettings,
+    pub bodies: Vec<RigidSphere>,
+    pub planes: Vec<StaticPlane>,
+}
+
+impl PhysicsWorld {
+    pub fn new(settings: PhysicsSettings) -> Self {
+        Self {
+            settings,
+            bodies: Vec::new(),
+            planes: Vec::new(),
+        }
+    }
+
+    /// Trajectory of the center of each body, keyed every time step.
+    /// ## PANICS if a body has no positive mass or the time step is not positive.
+    pub fn simulate(&self) -> Vec<Track<vec3>> {
+        if let Some(body) = self.bodies.iter().find(|body| body.mass <= 0.0) {
+            panic!("Rigid body mass should be positive, got {}!", body.mass);
+        }
+        let dt = self.settings.time_step;
+        if dt <= 0.0 {
+            panic!("Physics time step should be positive, got {}!", dt);
+        }
+
+        let mut bodies = self.bodies.clone();
+        let mut keys: Vec<Vec<Keyframe<vec3>>> = vec![Vec::new(); bodies.len()];
+        let key = |time, value| Keyframe {
+            time,
+            value,
+            interpolation: Interpolation::Linear,
+            easing: Easing::Linear,
+        };
+
+        let n_steps = (self.settings.duration / dt).ceil() as usize;
+        for i in 0..=n_steps {
+            let time = i as f64 * dt;
+            for (body, keys) in bodies.iter().zip(keys.iter_mut()) {
+                keys.push(key(time, body.center));
+            }
+            if i < n_steps {
+                self.step(&mut bodies, dt);
+            }
+        }
+
+        keys.into_iter().map(Track::new).collect()
+    }
+
+    /// Semi-implicit Euler step, then collisions are resolved by impulses.
+    fn step(&self, bodies: &mut [RigidSphere], dt: f64) {
+        for body in bodies.iter_mut() {
+            body.velocity += self.settings.gravity * dt;
+            body.center += body.velocity * dt;
+        }
+
+        // bounces slower than gravity builds up in a few steps come to rest instead
+        let resting_speed = 2.0 * self.settings.gravity.norm() * dt;
+
+        for body in bodies.iter_mut() {
+            for plane in &self.planes {
+                let normal = plane.normal.p_normalize();
+                let depth = body.radius - (body.center - plane.point).dot(&normal);
+                if depth <= 0.0 {
+                    continue;
+                }
+                body.center += depth * normal;
+
+                let vn = body.velocity.dot(&normal);
+                if vn < 0.0 {
+                    let e = if -vn < resting_speed {
+                        0.0
+                    } else {
+                        body.restitution
+                    };
+                    body.velocity -= (1.0 + e) * vn * normal;
+                }
+            }
+        }
+
+        for i in 0..bodies.len() {
+            for j in i + 1..bodies.len() {
+                let (left, right) = bodies.split_at_mut(j);
+                let (a, b) = (&mut left[i], &mut right[0]);
+
+                let delta = b.center - a.center;
+                let dist = delta.norm();
+                let depth = a.radius + b.radius - dist;
+                if depth <= 0.0 || dist == 0.0 {
+                    continue;
+                }
+                let normal = delta / dist;
+                let (wa, wb) = (1.0 / a.mass, 1.0 / b.mass);
+
+                // pushed apart in inverse proportion to their masses
+                a.center -= depth * wa / (wa + wb) * normal;
+                b.center += depth * wb / (wa + wb) * normal;
+
+                let vn = (b.velocity - a.velocity).dot(&normal);
+                if vn < 0.0 {
+                    let e = if -vn < resting_speed {
+                        0.0
+                    } else {
+                        a.restitution.min(b.restitution)
+                    };
+                    let impulse = -(1.0 + e) * vn / (wa + wb);
+                    a.velocity -= impulse * wa * normal;
+                    b.velocity += impulse * wb * normal;
+                }
+            }
+        }
+    }
+}
@@ -11,6 +11,7 @@ use crate::{
     math::panics::PanickingNormalize,
 };
 
+/// Moves by `delta_point` and turns to `new_normal` over one unit of animation time.
 #[derive(Debug)]
 pub struct AnimatedPlane {
     plane: Plane,
@@ -26,6 +27,20 @@ impl AnimatedPlane {
             new_normal: new_normal.p_normalize(),
         }
     }
+
+    /// The plane `time` after its current state.
+    pub fn at(&self, time: f64) -> Plane {
+        let normal = if self.new_normal == self.plane.normal {
+            self.new_normal
+        } else {
+            slerp(&self.plane.normal, &self.new_normal, time)
+        };
+        Plane {
+            point: self.plane.point + self.delta_point * time,
+            normal,
+            mat: self.plane.mat.clone(),
+        }
+    }
 }
 
 impl Entity for AnimatedPlane {
@@ -35,7 +50,11 @@ impl Entity for AnimatedPlane {
         ray: crate::tracer::ray::ray::Ray,
         interval: crate::math::interval::Interval,
     ) -> Option<crate::tracer::ray::hit::Hit> {
-        self.plane.hit_by(ray, interval)
+        if ray.time == 0.0 {
+            self.plane.hit_by(ray, interval)
+        } else {
+            self.at(ray.time).hit_by(ray, interval)
+        }
     }
 }
 
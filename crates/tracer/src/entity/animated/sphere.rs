@@ -8,6 +8,7 @@ use crate::{
 use glm::lerp;
 use std::sync::Arc;
 
+/// Moves by `delta` over one unit of animation time.
 #[derive(Debug)]
 pub struct AnimatedSphere {
     sphere: Sphere,
@@ -18,6 +19,15 @@ impl AnimatedSphere {
     pub fn new(sphere: Sphere, delta: vec3) -> Self {
         Self { sphere, delta }
     }
+
+    /// The sphere `time` after its current state.
+    pub fn at(&self, time: f64) -> Sphere {
+        Sphere {
+            center: self.sphere.center + self.delta * time,
+            radius: self.sphere.radius,
+            mat: self.sphere.mat.clone(),
+        }
+    }
 }
 
 impl Entity for AnimatedSphere {
@@ -27,7 +37,11 @@ impl Entity for AnimatedSphere {
         ray: crate::tracer::ray::ray::Ray,
         interval: crate::math::interval::Interval,
     ) -> Option<crate::tracer::ray::hit::Hit> {
-        self.sphere.hit_by(ray, interval)
+        if ray.time == 0.0 {
+            self.sphere.hit_by(ray, interval)
+        } else {
+            self.at(ray.time).hit_by(ray, interval)
+        }
     }
 }
 
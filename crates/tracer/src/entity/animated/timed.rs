@@ -0,0 +1,42 @@
+use crate::{
+    entity::traits::{AnimatedEntity, Entity},
+    math::{aabb::Aabb, interval::Interval},
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+use std::sync::Arc;
+
+/// Steps any entity through animation time by delaying the rays it is hit by,
+/// so entities that evaluate themselves at the ray time (e.g. `Keyframed`) follow
+/// the steps of an `AnimatedScene`. Still entities are unaffected.
+#[derive(Debug)]
+pub struct Timed {
+    pub entity: Arc<dyn Entity>,
+    /// Animation time reached by the steps so far.
+    pub time: f64,
+}
+
+impl Timed {
+    pub fn new(entity: Arc<dyn Entity>) -> Self {
+        Self { entity, time: 0.0 }
+    }
+}
+
+impl Entity for Timed {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        self.entity
+            .hit_by(ray.with_time(ray.time + self.time), interval)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.entity.bounding_box()
+    }
+}
+
+impl AnimatedEntity for Timed {
+    fn step(&self, t: f64) -> Arc<dyn AnimatedEntity> {
+        Arc::new(Self {
+            entity: self.entity.clone(),
+            time: self.time + t,
+        })
+    }
+}
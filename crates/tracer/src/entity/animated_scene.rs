@@ -1,8 +1,9 @@
-use super::{backgrounds::Background, traits::AnimatedEntity};
+use super::{atmosphere::Atmosphere, backgrounds::Background, traits::AnimatedEntity};
 use crate::{
     math::interval::Interval,
     tracer::ray::{hit::Hit, ray::Ray},
 };
+use rand::rngs::ThreadRng;
 use std::sync::Arc;
 
 #[derive(Debug)]
@@ -12,6 +13,7 @@ pub struct AnimatedScene {
     pub i_step: u32,
     pub dt: f64,
     pub background: Background,
+    pub atmosphere: Option<Atmosphere>,
 }
 
 impl AnimatedScene {
@@ -26,6 +28,7 @@ impl AnimatedScene {
             i_step: 0,
             dt: 1.0 / n_step as f64,
             background,
+            atmosphere: None,
         }
     }
 
@@ -56,4 +59,13 @@ impl AnimatedScene {
 
         scene_hit
     }
+
+    /// Nearest hit along the ray, taking scattering inside the atmosphere into account.
+    pub fn hit_through_atmosphere(&self, ray: Ray, rng: &mut ThreadRng) -> Option<Hit> {
+        let hit = self.hit_by(ray);
+        match &self.atmosphere {
+            Some(atmosphere) => atmosphere.scatter_before(ray, hit, rng),
+            None => hit,
+        }
+    }
 }
@@ -0,0 +1,110 @@
+use rand::rngs::ThreadRng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helpers::types::{color, vec3},
+    materials::material::FragMaterial,
+    math::distributions::sample_uniform_01,
+    tracer::ray::{
+        hit::{Hit, Normal},
+        ray::Ray,
+    },
+};
+
+fn default_horizon() -> f64 {
+    1000.0
+}
+
+/// A participating medium filling the whole scene.
+///
+/// Rays that hit nothing are considered to travel `horizon` units through the medium
+/// before reaching the background.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Atmosphere {
+    Homogeneous {
+        density: f64,
+        albedo: color,
+        #[serde(default = "default_horizon")]
+        horizon: f64,
+    },
+    /// Density decays exponentially with height:
+    /// - `density * exp(-falloff * (y - base))`
+    HeightFalloff {
+        density: f64,
+        albedo: color,
+        base: f64,
+        falloff: f64,
+        #[serde(default = "default_horizon")]
+        horizon: f64,
+    },
+}
+
+impl Atmosphere {
+    /// Samples a free-flight distance along `ray`.
+    /// Returns a scattering hit if the ray is scattered before reaching `hit`,
+    /// otherwise `hit` is returned unchanged.
+    pub fn scatter_before(
+        &self,
+        ray: Ray,
+        hit: Option<Hit>,
+        rng: &mut ThreadRng,
+    ) -> Option<Hit> {
+        let tmax = hit.as_ref().map_or(self.horizon(), |hit| hit.t);
+        // optical depth reached before scattering
+        let depth = -(1.0 - sample_uniform_01(rng)).ln();
+
+        let t = match *self {
+            Atmosphere::Homogeneous { density, .. } => depth / density,
+            Atmosphere::HeightFalloff {
+                density,
+                base,
+                falloff,
+                ..
+            } => {
+                let k0 = density * (-falloff * (ray.orig.y - base)).exp();
+                let fdy = falloff * ray.dir.y;
+                if fdy.abs() <= f64::EPSILON {
+                    depth / k0
+                } else {
+                    let x = 1.0 - depth * fdy / k0;
+                    if x <= 0.0 {
+                        // the total optical depth along the ray is not enough
+                        return hit;
+                    }
+                    -x.ln() / fdy
+                }
+            }
+        };
+
+        if t > ray.tmin && t < tmax {
+            Some(Hit {
+                in_dir: ray.dir,
+                pos: ray.at(t),
+                material: FragMaterial::Isotropic {
+                    albedo: self.albedo(),
+                },
+                // not a material of the table
+                material_id: 0,
+                t,
+                normal: Normal::Outward(vec3::new(1.0, 0.0, 0.0)),
+            })
+        } else {
+            hit
+        }
+    }
+
+    fn horizon(&self) -> f64 {
+        match self {
+            Atmosphere::Homogeneous { horizon, .. } => *horizon,
+            Atmosphere::HeightFalloff { horizon, .. } => *horizon,
+        }
+    }
+
+    fn albedo(&self) -> color {
+        match self {
+            Atmosphere::Homogeneous { albedo, .. } => *albedo,
+            Atmosphere::HeightFalloff { albedo, .. } => *albedo,
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{math::aabb::Aabb, tracer::ray::ray::Ray};
+
+/// Items per leaf, below which nodes are not split.
+const LEAF_SIZE: usize = 4;
+
+#[derive(Debug)]
+enum BvhNode {
+    /// Items `order[start..start + count]`.
+    Leaf {
+        bounds: Aabb,
+        start: usize,
+        count: usize,
+    },
+    /// The left child directly follows its parent.
+    Inner { bounds: Aabb, right: usize },
+}
+
+impl BvhNode {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf { bounds, .. } => bounds,
+            BvhNode::Inner { bounds, .. } => bounds,
+        }
+    }
+}
+
+/// Bounding volume hierarchy over items known by their index and bounding box,
+/// split at the median along the longest axis.
+#[derive(Debug)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    order: Vec<usize>,
+}
+
+impl Bvh {
+    pub fn new(bounds: &[Aabb]) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::with_capacity(2 * bounds.len() / LEAF_SIZE + 1),
+            order: (0..bounds.len()).collect(),
+        };
+        if !bounds.is_empty() {
+            bvh.build(bounds, 0, bounds.len());
+        }
+        bvh
+    }
+
+    /// `None` if there is no item.
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.nodes.first().map(|node| *node.bounds())
+    }
+
+    fn build(&mut self, bounds: &[Aabb], start: usize, end: usize) -> usize {
+        let items = &mut self.order[start..end];
+        let node_bounds = items
+            .iter()
+            .map(|&i| bounds[i])
+            .reduce(|a, b| a.union(&b))
+            .unwrap();
+
+        let index = self.nodes.len();
+        if items.len() <= LEAF_SIZE {
+            self.nodes.push(BvhNode::Leaf {
+                bounds: node_bounds,
+                start,
+                count: items.len(),
+            });
+            return index;
+        }
+
+        let centers = items.iter().map(|&i| bounds[i].center());
+        let spread = Aabb::from_points(centers).size();
+        let axis = spread.imax();
+
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |&a, &b| {
+            bounds[a].center()[axis].total_cmp(&bounds[b].center()[axis])
+        });
+
+        self.nodes.push(BvhNode::Inner {
+            bounds: node_bounds,
+            right: 0,
+        });
+        self.build(bounds, start, start + mid);
+        let right_index = self.build(bounds, start + mid, end);
+        if let BvhNode::Inner { right, .. } = &mut self.nodes[index] {
+            *right = right_index;
+        }
+        index
+    }
+
+    /// Closest hit among the items whose boxes `ray` passes through within `(tmin, tmax)`.
+    /// - `hit`: tests item `i` for a hit closer than the given `t`,
+    ///   returning the `t` of the hit and anything else to keep.
+    pub fn closest<T>(
+        &self,
+        ray: &Ray,
+        tmin: f64,
+        tmax: f64,
+        mut hit: impl FnMut(usize, f64) -> Option<(f64, T)>,
+    ) -> Option<T> {
+        let mut best = None;
+        let mut tmax = tmax;
+
+        let mut stack = Vec::with_capacity(64);
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            match node.bounds().clip(ray) {
+                Some((tnear, tfar)) if tnear < tmax && tfar > tmin => {}
+                _ => continue,
+            }
+
+            match node {
+                BvhNode::Leaf { start, count, .. } => {
+                    for &item in &self.order[*start..start + count] {
+                        if let Some((t, found)) = hit(item, tmax) {
+                            tmax = t;
+                            best = Some(found);
+                        }
+                    }
+                }
+                BvhNode::Inner { right, .. } => {
+                    // visit the nearer child first
+                    let left = index + 1;
+                    let near = |child: usize| {
+                        self.nodes[child]
+                            .bounds()
+                            .clip(ray)
+                            .map_or(f64::INFINITY, |(tnear, _)| tnear)
+                    };
+                    if near(left) <= near(*right) {
+                        stack.push(*right);
+                        stack.push(left);
+                    } else {
+                        stack.push(left);
+                        stack.push(*right);
+                    }
+                }
+            }
+        }
+
+        best
+    }
+}
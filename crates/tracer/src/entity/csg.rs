@@ -0,0 +1,143 @@
+use super::traits::{Entity, Solid};
+use crate::{
+    helpers::types::vec3,
+    materials::material::FragMaterial,
+    math::{aabb::Aabb, interval::Interval},
+    tracer::ray::{
+        hit::{Hit, Normal},
+        ray::Ray,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Where a ray crosses the boundary of a solid.
+#[derive(Debug, Clone, Copy)]
+pub struct Crossing {
+    pub t: f64,
+    /// Always points out of the solid.
+    pub normal: vec3,
+    pub material: FragMaterial,
+    pub material_id: u32,
+}
+
+/// The part of a ray's line that lies inside a solid.
+#[derive(Debug, Clone, Copy)]
+pub struct Span {
+    pub enter: Crossing,
+    pub exit: Crossing,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum CsgOp {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOp {
+    fn inside(self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOp::Union => in_left || in_right,
+            CsgOp::Intersection => in_left && in_right,
+            CsgOp::Difference => in_left && !in_right,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Csg {
+    pub op: CsgOp,
+    pub left: Arc<dyn Solid>,
+    pub right: Arc<dyn Solid>,
+}
+
+impl Csg {
+    pub fn new(op: CsgOp, left: Arc<dyn Solid>, right: Arc<dyn Solid>) -> Self {
+        Self { op, left, right }
+    }
+}
+
+impl Solid for Csg {
+    fn spans(&self, ray: Ray) -> Vec<Span> {
+        // (crossing, is_enter, is_left)
+        let mut events = Vec::new();
+        for (spans, is_left) in [(self.left.spans(ray), true), (self.right.spans(ray), false)] {
+            for span in spans {
+                events.push((span.enter, true, is_left));
+                events.push((span.exit, false, is_left));
+            }
+        }
+        events.sort_by(|e1, e2| e1.0.t.total_cmp(&e2.0.t));
+
+        let mut spans = Vec::new();
+        let (mut in_left, mut in_right) = (false, false);
+        let mut enter = None;
+
+        for (mut crossing, is_enter, is_left) in events {
+            let was_inside = self.op.inside(in_left, in_right);
+            if is_left {
+                in_left = is_enter;
+            } else {
+                in_right = is_enter;
+            }
+            let inside = self.op.inside(in_left, in_right);
+
+            if let CsgOp::Difference = self.op {
+                if !is_left {
+                    // the surface of the subtracted solid faces the other way
+                    crossing.normal = -crossing.normal;
+                }
+            }
+
+            if !was_inside && inside {
+                enter = Some(crossing);
+            } else if was_inside && !inside {
+                let enter = enter.take().expect("Span should be entered before exited!");
+                spans.push(Span {
+                    enter,
+                    exit: crossing,
+                });
+            }
+        }
+
+        spans
+    }
+}
+
+impl Entity for Csg {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        hit_from_spans(ray, interval, &self.spans(ray))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let left = self.left.bounding_box()?;
+        match self.op {
+            CsgOp::Union => Some(left.union(&self.right.bounding_box()?)),
+            CsgOp::Intersection => Some(left.intersection(&self.right.bounding_box()?)),
+            CsgOp::Difference => Some(left),
+        }
+    }
+}
+
+/// Nearest crossing within `interval`, entering through an outward normal
+/// and leaving through an inward one.
+pub fn hit_from_spans(ray: Ray, interval: Interval, spans: &[Span]) -> Option<Hit> {
+    spans
+        .iter()
+        .flat_map(|span| {
+            [
+                (span.enter, Normal::Outward(span.enter.normal)),
+                (span.exit, Normal::Inward(-span.exit.normal)),
+            ]
+        })
+        .find(|(crossing, _)| interval.contains(crossing.t))
+        .map(|(crossing, normal)| Hit {
+            in_dir: ray.dir,
+            pos: ray.at(crossing.t),
+            material: crossing.material,
+            material_id: crossing.material_id,
+            t: crossing.t,
+            normal,
+        })
+}
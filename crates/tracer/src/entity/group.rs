@@ -0,0 +1,40 @@
+use super::traits::Entity;
+use crate::{
+    math::{aabb::Aabb, interval::Interval},
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+use std::sync::Arc;
+
+/// Several entities hit as one, e.g. a grouped prototype placed by `Instance`s.
+#[derive(Debug)]
+pub struct Group {
+    pub entities: Vec<Arc<dyn Entity>>,
+}
+
+impl Group {
+    pub fn new(entities: Vec<Arc<dyn Entity>>) -> Self {
+        Self { entities }
+    }
+}
+
+impl Entity for Group {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        let mut interval = interval;
+        let mut nearest_hit = None;
+
+        for ent in &self.entities {
+            if let Some(hit) = ent.hit_by(ray, interval) {
+                interval = interval.clamp_high(hit.t);
+                nearest_hit = Some(hit);
+            }
+        }
+
+        nearest_hit
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let mut boxes = self.entities.iter().map(|ent| ent.bounding_box());
+        let first = boxes.next()??;
+        boxes.try_fold(first, |aabb, other| Some(aabb.union(&other?)))
+    }
+}
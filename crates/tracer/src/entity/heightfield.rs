@@ -0,0 +1,234 @@
+use super::{
+    analytic::commons::{nearest_hit, ray_triangle, SurfacePoint},
+    traits::Entity,
+};
+use crate::{
+    helpers::types::{vec2, vec3},
+    materials::material::Material,
+    math::{aabb::Aabb, interval::Interval, panics::PanickingNormalize},
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+
+/// Terrain over a regular grid of height samples, two triangles per cell.
+///
+/// Sample `(ix, iz)` lies at `min + (ix * dx, height * size.y, iz * dz)`, so the grid
+/// spans `size.x` by `size.z` and heights in \[0, 1\] span `size.y`.
+/// UVs run along x and z, a texture drapes over the terrain as seen from above.
+#[derive(Debug)]
+pub struct Heightfield {
+    pub min: vec3,
+    pub size: vec3,
+    pub mat: Material,
+
+    nx: usize,
+    nz: usize,
+    /// `nx * nz` world positions, row by row along x.
+    points: Vec<vec3>,
+    /// Smooth normal per sample.
+    normals: Vec<vec3>,
+    /// Lowest and highest height per cell, `(nx - 1) * (nz - 1)` of them.
+    cell_range: Vec<(f64, f64)>,
+    bounds: Aabb,
+}
+
+impl Heightfield {
+    /// - `heights`: `nx * nz` samples in \[0, 1\], row by row along x.
+    ///
+    /// ## PANICS if the grid is smaller than 2 by 2 or `heights` does not fill it.
+    pub fn new(
+        heights: &[f64],
+        nx: usize,
+        nz: usize,
+        min: vec3,
+        size: vec3,
+        mat: Material,
+    ) -> Self {
+        if nx < 2 || nz < 2 {
+            panic!("Heightfield needs at least 2 by 2 samples!");
+        }
+        if heights.len() != nx * nz {
+            panic!("Expected {} height samples, got {}", nx * nz, heights.len());
+        }
+
+        let dx = size.x / (nx - 1) as f64;
+        let dz = size.z / (nz - 1) as f64;
+        let h = |ix: usize, iz: usize| heights[iz * nx + ix] * size.y;
+
+        let points = (0..nz)
+            .flat_map(|iz| {
+                (0..nx).map(move |ix| min + vec3::new(ix as f64 * dx, 0.0, iz as f64 * dz))
+            })
+            .zip(heights)
+            .map(|(p, height)| p + vec3::new(0.0, height * size.y, 0.0))
+            .collect();
+
+        let normals = (0..nz)
+            .flat_map(|iz| (0..nx).map(move |ix| (ix, iz)))
+            .map(|(ix, iz)| {
+                // central differences, one-sided at the borders
+                let (x0, x1) = (ix.saturating_sub(1), (ix + 1).min(nx - 1));
+                let (z0, z1) = (iz.saturating_sub(1), (iz + 1).min(nz - 1));
+                let slope_x = (h(x1, iz) - h(x0, iz)) / ((x1 - x0) as f64 * dx);
+                let slope_z = (h(ix, z1) - h(ix, z0)) / ((z1 - z0) as f64 * dz);
+                vec3::new(-slope_x, 1.0, -slope_z).p_normalize()
+            })
+            .collect();
+
+        let cell_range = (0..nz - 1)
+            .flat_map(|iz| (0..nx - 1).map(move |ix| (ix, iz)))
+            .map(|(ix, iz)| {
+                let corners = [h(ix, iz), h(ix + 1, iz), h(ix, iz + 1), h(ix + 1, iz + 1)];
+                let low = corners.iter().copied().fold(f64::INFINITY, f64::min);
+                let high = corners.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                (min.y + low, min.y + high)
+            })
+            .collect();
+
+        let (low, high) = heights
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &h| {
+                (low.min(h), high.max(h))
+            });
+        let bounds = Aabb::new(
+            vec3::new(min.x, min.y + low * size.y, min.z),
+            vec3::new(min.x + size.x, min.y + high * size.y, min.z + size.z),
+        );
+
+        Self {
+            min,
+            size,
+            mat,
+            nx,
+            nz,
+            points,
+            normals,
+            cell_range,
+            bounds,
+        }
+    }
+
+    /// Heights are the luminance of the image at `path`, image rows run along z.
+    ///
+    /// ## PANICS if the image cannot be read.
+    pub fn from_image(path: &str, min: vec3, size: vec3, mat: Material) -> Self {
+        let img = image::open(path)
+            .unwrap_or_else(|_| panic!("Heightmap not found: {}", path))
+            .to_luma32f();
+        let heights: Vec<f64> = img.pixels().map(|px| px[0] as f64).collect();
+        Self::new(
+            &heights,
+            img.width() as usize,
+            img.height() as usize,
+            min,
+            size,
+            mat,
+        )
+    }
+
+    /// Nearest hit on the two triangles of cell `(ix, iz)`.
+    fn hit_cell(&self, ray: Ray, interval: Interval, ix: usize, iz: usize) -> Option<Hit> {
+        let corners = [
+            iz * self.nx + ix,
+            iz * self.nx + ix + 1,
+            (iz + 1) * self.nx + ix,
+            (iz + 1) * self.nx + ix + 1,
+        ];
+        let triangles = [
+            [corners[0], corners[2], corners[1]],
+            [corners[1], corners[2], corners[3]],
+        ];
+
+        let points: Vec<SurfacePoint> = triangles
+            .iter()
+            .filter_map(|&[a, b, c]| {
+                let (t, u, v) =
+                    ray_triangle(&ray, self.points[a], self.points[b], self.points[c])?;
+                let w = 1.0 - u - v;
+                let normal = w * self.normals[a] + u * self.normals[b] + v * self.normals[c];
+                let pos = ray.at(t);
+                Some(SurfacePoint {
+                    t,
+                    normal: normal.p_normalize(),
+                    uv: vec2::new(
+                        (pos.x - self.min.x) / self.size.x,
+                        (pos.z - self.min.z) / self.size.z,
+                    ),
+                })
+            })
+            .collect();
+
+        nearest_hit(ray, interval, &points, &self.mat, false)
+    }
+}
+
+impl Entity for Heightfield {
+    /// Walks the cells under the ray in order (grid DDA), skipping cells whose
+    /// height range the ray passes above or below.
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        let (tnear, tfar) = self.bounds.clip(&ray)?;
+        let tmin = tnear.max(interval.min().unwrap_or(f64::NEG_INFINITY));
+        let tmax = tfar.min(interval.max().unwrap_or(f64::INFINITY));
+        if tmin > tmax {
+            return None;
+        }
+
+        let cell_size = vec2::new(
+            self.size.x / (self.nx - 1) as f64,
+            self.size.z / (self.nz - 1) as f64,
+        );
+        let n_cells = [self.nx - 1, self.nz - 1];
+        let start = ray.at(tmin);
+        let start = vec2::new(start.x - self.min.x, start.z - self.min.z);
+        let dir = vec2::new(ray.dir.x, ray.dir.z);
+
+        let mut cell = [0; 2];
+        let mut step = [0isize; 2];
+        let mut t_next = [f64::INFINITY; 2];
+        let mut t_delta = [f64::INFINITY; 2];
+        for axis in 0..2 {
+            let index = (start[axis] / cell_size[axis]).floor().max(0.0) as usize;
+            cell[axis] = index.min(n_cells[axis] - 1);
+            if dir[axis] > 0.0 {
+                step[axis] = 1;
+                t_delta[axis] = cell_size[axis] / dir[axis];
+                let boundary = (cell[axis] + 1) as f64 * cell_size[axis];
+                t_next[axis] = tmin + (boundary - start[axis]) / dir[axis];
+            } else if dir[axis] < 0.0 {
+                step[axis] = -1;
+                t_delta[axis] = -cell_size[axis] / dir[axis];
+                let boundary = cell[axis] as f64 * cell_size[axis];
+                t_next[axis] = tmin + (boundary - start[axis]) / dir[axis];
+            }
+        }
+
+        let mut t_enter = tmin;
+        loop {
+            let t_exit = t_next[0].min(t_next[1]).min(tmax);
+
+            let (y0, y1) = (ray.at(t_enter).y, ray.at(t_exit).y);
+            let (low, high) = self.cell_range[cell[1] * n_cells[0] + cell[0]];
+            if y0.min(y1) <= high && y0.max(y1) >= low {
+                if let Some(hit) = self.hit_cell(ray, interval, cell[0], cell[1]) {
+                    return Some(hit);
+                }
+            }
+
+            if t_exit >= tmax {
+                return None;
+            }
+
+            let axis = if t_next[0] < t_next[1] { 0 } else { 1 };
+            let next = cell[axis] as isize + step[axis];
+            if next < 0 || next >= n_cells[axis] as isize {
+                return None;
+            }
+            cell[axis] = next as usize;
+            t_enter = t_next[axis];
+            t_next[axis] += t_delta[axis];
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
@@ -0,0 +1,71 @@
+use super::traits::Entity;
+use crate::{
+    math::{aabb::Aabb, interval::Interval, panics::PanickingNormalize, transform::Transform},
+    tracer::ray::{
+        hit::{Hit, Normal},
+        ray::Ray,
+    },
+};
+use std::sync::Arc;
+
+/// A shared entity placed in the world by a transform.
+#[derive(Debug)]
+pub struct Instance {
+    pub entity: Arc<dyn Entity>,
+    /// Object space to world space.
+    pub transform: Transform,
+}
+
+impl Instance {
+    pub fn new(entity: Arc<dyn Entity>, transform: Transform) -> Self {
+        Self { entity, transform }
+    }
+}
+
+impl Entity for Instance {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        hit_transformed(self.entity.as_ref(), &self.transform, ray, interval)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.entity
+            .bounding_box()
+            .map(|aabb| aabb.transformed(&self.transform))
+    }
+}
+
+/// Hits `entity` placed in the world by `transform` (object space to world space).
+pub fn hit_transformed(
+    entity: &dyn Entity,
+    transform: &Transform,
+    ray: Ray,
+    interval: Interval,
+) -> Option<Hit> {
+    let world_to_object = transform.inverse();
+
+    // length of a unit world step in object space
+    let dir = world_to_object.vector(ray.dir);
+    let scale = dir.p_magnitude();
+
+    let local_ray = Ray {
+        orig: world_to_object.point(ray.orig),
+        dir: dir / scale,
+        tmin: ray.tmin * scale,
+        ..ray
+    };
+
+    let hit = entity.hit_by(local_ray, interval.scaled(scale))?;
+
+    let normal = match hit.normal {
+        Normal::Outward(n) => Normal::Outward(transform.normal(n).p_normalize()),
+        Normal::Inward(n) => Normal::Inward(transform.normal(n).p_normalize()),
+    };
+
+    Some(Hit {
+        in_dir: ray.dir,
+        pos: transform.point(hit.pos),
+        t: hit.t / scale,
+        normal,
+        ..hit
+    })
+}
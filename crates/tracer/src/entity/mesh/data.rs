@@ -0,0 +1,89 @@
+use super::{errors::MeshError, ply::load_ply, stl::load_stl};
+use crate::{
+    helpers::types::{color, vec2, vec3},
+    math::aabb::Aabb,
+};
+
+/// Indexed triangles sharing vertex buffers.
+/// Optional attributes have one entry per vertex, face groups one entry per triangle.
+#[derive(Debug, Clone, Default)]
+pub struct MeshData {
+    pub positions: Vec<vec3>,
+    pub normals: Option<Vec<vec3>>,
+    pub colors: Option<Vec<color>>,
+    pub uvs: Option<Vec<vec2>>,
+    pub indices: Vec<[u32; 3]>,
+    /// Face group of each triangle, which picks its material; all in group 0 if `None`.
+    pub groups: Option<Vec<u32>>,
+}
+
+impl MeshData {
+    /// Reads a `.ply` or `.stl` file, by extension.
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let extension = path.rsplit('.').next().unwrap_or_default();
+        match extension.to_ascii_lowercase().as_str() {
+            "ply" => load_ply(path),
+            "stl" => load_stl(path),
+            _ => Err(MeshError::Unsupported {
+                format: "mesh".into(),
+                feature: format!("file extension .{}", extension),
+            }
+            .into()),
+        }
+    }
+
+    pub fn n_triangles(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn vertices(&self, i: usize) -> [vec3; 3] {
+        self.indices[i].map(|v| self.positions[v as usize])
+    }
+
+    /// ## PANICS if the mesh has no vertex.
+    pub fn bounds(&self) -> Aabb {
+        Aabb::from_points(self.positions.iter().copied())
+    }
+
+    pub fn group(&self, i: usize) -> usize {
+        self.groups.as_ref().map_or(0, |groups| groups[i] as usize)
+    }
+
+    /// Concatenates `parts`, part `i` becoming face group `i`.
+    /// Attributes missing from any part are dropped.
+    pub fn merged(parts: Vec<MeshData>) -> Self {
+        let all = |has: fn(&MeshData) -> bool| parts.iter().all(has);
+        let mut merged = MeshData {
+            normals: all(|part| part.normals.is_some()).then(Vec::new),
+            colors: all(|part| part.colors.is_some()).then(Vec::new),
+            uvs: all(|part| part.uvs.is_some()).then(Vec::new),
+            groups: Some(Vec::new()),
+            ..Default::default()
+        };
+
+        for (group, part) in parts.into_iter().enumerate() {
+            let offset = merged.positions.len() as u32;
+            merged.positions.extend(part.positions);
+            if let (Some(all), Some(part)) = (merged.normals.as_mut(), part.normals) {
+                all.extend(part);
+            }
+            if let (Some(all), Some(part)) = (merged.colors.as_mut(), part.colors) {
+                all.extend(part);
+            }
+            if let (Some(all), Some(part)) = (merged.uvs.as_mut(), part.uvs) {
+                all.extend(part);
+            }
+            let n = part.indices.len();
+            merged
+                .indices
+                .extend(part.indices.into_iter().map(|tri| tri.map(|v| v + offset)));
+            merged
+                .groups
+                .as_mut()
+                .unwrap()
+                .extend((0..n).map(|_| group as u32));
+        }
+
+        merged
+    }
+}
@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MeshError {
+    #[error("Malformed {} file: {}", format, reason)]
+    Malformed { format: String, reason: String },
+    #[error("Unsupported {} feature: {}", format, feature)]
+    Unsupported { format: String, feature: String },
+}
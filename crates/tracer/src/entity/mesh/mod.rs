@@ -0,0 +1,5 @@
+pub mod data;
+pub mod errors;
+pub mod ply;
+pub mod stl;
+pub mod triangle_mesh;
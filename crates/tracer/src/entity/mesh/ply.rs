@@ -0,0 +1,355 @@
+use super::{data::MeshData, errors::MeshError};
+use crate::helpers::types::{color, vec2, vec3};
+use std::{fs, str::SplitAsciiWhitespace};
+
+/// Reads an ASCII or binary PLY file.
+/// Besides positions, per-vertex normals (`nx, ny, nz`), colors (`red, green, blue`)
+/// and texture coordinates (`u, v` or `s, t`) are kept if present.
+/// Polygons are split into triangle fans, other elements are skipped.
+pub fn load_ply(path: &str) -> anyhow::Result<MeshData> {
+    let bytes = fs::read(path)?;
+
+    let marker = b"end_header";
+    let header_end = bytes
+        .windows(marker.len())
+        .position(|w| w == marker)
+        .ok_or_else(|| malformed("missing end_header"))?;
+    // the body starts after the line break following the marker
+    let body_start = bytes[header_end..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(bytes.len(), |i| header_end + i + 1);
+
+    let header = String::from_utf8_lossy(&bytes[..header_end]);
+    let (format, elements) = parse_header(&header)?;
+
+    let text;
+    let mut body = match format {
+        Format::Ascii => {
+            text = String::from_utf8_lossy(&bytes[body_start..]).into_owned();
+            Body::Ascii(text.split_ascii_whitespace())
+        }
+        Format::Binary { big_endian } => Body::Binary {
+            bytes: &bytes[body_start..],
+            pos: 0,
+            big_endian,
+        },
+    };
+
+    let mut mesh = MeshData::default();
+    for element in &elements {
+        match element.name.as_str() {
+            "vertex" => read_vertices(element, &mut body, &mut mesh)?,
+            "face" => read_faces(element, &mut body, &mut mesh)?,
+            _ => {
+                for _ in 0..element.count {
+                    for property in &element.properties {
+                        body.skip(property)?;
+                    }
+                }
+            }
+        }
+    }
+
+    let n_vertices = mesh.positions.len() as u32;
+    if mesh.indices.iter().flatten().any(|&i| i >= n_vertices) {
+        return Err(malformed("face refers to a missing vertex").into());
+    }
+
+    Ok(mesh)
+}
+
+/// Index read as a number, which a float or signed type could make negative or fractional.
+fn vertex_index(index: f64) -> Result<u32, MeshError> {
+    if index.is_finite() && index >= 0.0 && index.fract() == 0.0 && index <= u32::MAX as f64 {
+        Ok(index as u32)
+    } else {
+        Err(malformed("face refers to a missing vertex"))
+    }
+}
+
+fn malformed(reason: &str) -> MeshError {
+    MeshError::Malformed {
+        format: "PLY".into(),
+        reason: reason.into(),
+    }
+}
+
+fn unsupported(feature: &str) -> MeshError {
+    MeshError::Unsupported {
+        format: "PLY".into(),
+        feature: feature.into(),
+    }
+}
+
+enum Format {
+    Ascii,
+    Binary { big_endian: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> anyhow::Result<Self> {
+        Ok(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return Err(unsupported(&format!("scalar type {}", name)).into()),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+}
+
+enum Property {
+    Scalar {
+        name: String,
+        ty: Scalar,
+    },
+    List {
+        name: String,
+        count: Scalar,
+        item: Scalar,
+    },
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+fn parse_header(header: &str) -> anyhow::Result<(Format, Vec<Element>)> {
+    let mut lines = header.lines().map(str::trim);
+    if lines.next() != Some("ply") {
+        return Err(malformed("missing ply magic").into());
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for line in lines {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["format", "ascii", _] => format = Some(Format::Ascii),
+            ["format", "binary_little_endian", _] => {
+                format = Some(Format::Binary { big_endian: false })
+            }
+            ["format", "binary_big_endian", _] => {
+                format = Some(Format::Binary { big_endian: true })
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count.parse()?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => elements
+                .last_mut()
+                .ok_or_else(|| malformed("property before any element"))?
+                .properties
+                .push(Property::List {
+                    name: name.to_string(),
+                    count: Scalar::parse(count)?,
+                    item: Scalar::parse(item)?,
+                }),
+            ["property", ty, name] => elements
+                .last_mut()
+                .ok_or_else(|| malformed("property before any element"))?
+                .properties
+                .push(Property::Scalar {
+                    name: name.to_string(),
+                    ty: Scalar::parse(ty)?,
+                }),
+            _ => {} // comments, obj_info and blank lines
+        }
+    }
+
+    let format = format.ok_or_else(|| malformed("missing format"))?;
+    Ok((format, elements))
+}
+
+enum Body<'a> {
+    Ascii(SplitAsciiWhitespace<'a>),
+    Binary {
+        bytes: &'a [u8],
+        pos: usize,
+        big_endian: bool,
+    },
+}
+
+impl Body<'_> {
+    fn read(&mut self, ty: Scalar) -> anyhow::Result<f64> {
+        match self {
+            Body::Ascii(words) => {
+                let word = words
+                    .next()
+                    .ok_or_else(|| malformed("unexpected end of file"))?;
+                Ok(word.parse()?)
+            }
+            Body::Binary {
+                bytes,
+                pos,
+                big_endian,
+            } => {
+                let raw = bytes
+                    .get(*pos..*pos + ty.size())
+                    .ok_or_else(|| malformed("unexpected end of file"))?;
+                *pos += ty.size();
+
+                macro_rules! decode {
+                    ($t:ty) => {{
+                        let raw = raw.try_into().unwrap();
+                        (if *big_endian {
+                            <$t>::from_be_bytes(raw)
+                        } else {
+                            <$t>::from_le_bytes(raw)
+                        }) as f64
+                    }};
+                }
+                Ok(match ty {
+                    Scalar::I8 => decode!(i8),
+                    Scalar::U8 => decode!(u8),
+                    Scalar::I16 => decode!(i16),
+                    Scalar::U16 => decode!(u16),
+                    Scalar::I32 => decode!(i32),
+                    Scalar::U32 => decode!(u32),
+                    Scalar::F32 => decode!(f32),
+                    Scalar::F64 => decode!(f64),
+                })
+            }
+        }
+    }
+
+    fn read_list(&mut self, count: Scalar, item: Scalar) -> anyhow::Result<Vec<f64>> {
+        let n = self.read(count)? as usize;
+        (0..n).map(|_| self.read(item)).collect()
+    }
+
+    fn skip(&mut self, property: &Property) -> anyhow::Result<()> {
+        match property {
+            Property::Scalar { ty, .. } => self.read(*ty).map(|_| ()),
+            Property::List { count, item, .. } => self.read_list(*count, *item).map(|_| ()),
+        }
+    }
+}
+
+/// Vertex properties kept, by their position in a record.
+const SLOTS: [&[&str]; 11] = [
+    &["x"],
+    &["y"],
+    &["z"],
+    &["nx"],
+    &["ny"],
+    &["nz"],
+    &["red", "r", "diffuse_red"],
+    &["green", "g", "diffuse_green"],
+    &["blue", "b", "diffuse_blue"],
+    &["u", "s", "texture_u", "texture_s"],
+    &["v", "t", "texture_v", "texture_t"],
+];
+
+fn read_vertices(
+    element: &Element,
+    body: &mut Body,
+    mesh: &mut MeshData,
+) -> anyhow::Result<()> {
+    let slot_of = |name: &str| SLOTS.iter().position(|names| names.contains(&name));
+    let slots: Vec<Option<usize>> = element
+        .properties
+        .iter()
+        .map(|property| match property {
+            Property::Scalar { name, .. } => slot_of(name),
+            Property::List { .. } => None,
+        })
+        .collect();
+    let has = |mut range: std::ops::Range<usize>| range.all(|slot| slots.contains(&Some(slot)));
+
+    if !has(0..3) {
+        return Err(malformed("vertex is missing x, y or z").into());
+    }
+    let mut normals = has(3..6).then(Vec::new);
+    let mut colors = has(6..9).then(Vec::new);
+    let mut uvs = has(9..11).then(Vec::new);
+
+    for _ in 0..element.count {
+        let mut record = [0.0; SLOTS.len()];
+        for (property, slot) in element.properties.iter().zip(&slots) {
+            match (property, slot) {
+                (Property::Scalar { ty, .. }, Some(slot)) => {
+                    record[*slot] = body.read(*ty)?;
+                    // integer colors are normalized to [0, 1]
+                    if (6..9).contains(slot) {
+                        match ty {
+                            Scalar::U8 => record[*slot] /= u8::MAX as f64,
+                            Scalar::U16 => record[*slot] /= u16::MAX as f64,
+                            _ => {}
+                        }
+                    }
+                }
+                (property, _) => body.skip(property)?,
+            }
+        }
+
+        mesh.positions
+            .push(vec3::new(record[0], record[1], record[2]));
+        if let Some(normals) = normals.as_mut() {
+            normals.push(vec3::new(record[3], record[4], record[5]));
+        }
+        if let Some(colors) = colors.as_mut() {
+            colors.push(color::new(record[6], record[7], record[8]));
+        }
+        if let Some(uvs) = uvs.as_mut() {
+            uvs.push(vec2::new(record[9], record[10]));
+        }
+    }
+
+    mesh.normals = normals;
+    mesh.colors = colors;
+    mesh.uvs = uvs;
+    Ok(())
+}
+
+fn read_faces(element: &Element, body: &mut Body, mesh: &mut MeshData) -> anyhow::Result<()> {
+    for _ in 0..element.count {
+        for property in &element.properties {
+            match property {
+                Property::List { name, count, item }
+                    if name == "vertex_indices" || name == "vertex_index" =>
+                {
+                    let polygon = body
+                        .read_list(*count, *item)?
+                        .into_iter()
+                        .map(vertex_index)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    for i in 1..polygon.len().saturating_sub(1) {
+                        mesh.indices.push([polygon[0], polygon[i], polygon[i + 1]]);
+                    }
+                }
+                property => body.skip(property)?,
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use super::{data::MeshData, errors::MeshError};
+use crate::helpers::types::vec3;
+use std::{collections::HashMap, fs};
+
+/// Reads an ASCII or binary STL file.
+/// Corners at the same position are shared, facet normals are ignored in favor of
+/// the winding order.
+pub fn load_stl(path: &str) -> anyhow::Result<MeshData> {
+    let bytes = fs::read(path)?;
+
+    let triangles = if is_ascii(&bytes) {
+        ascii_triangles(&String::from_utf8_lossy(&bytes))?
+    } else {
+        binary_triangles(&bytes)?
+    };
+    if triangles.is_empty() {
+        return Err(malformed("no triangles").into());
+    }
+
+    let mut mesh = MeshData::default();
+    let mut index_of = HashMap::new();
+    for triangle in triangles {
+        let tri = triangle.map(|p| {
+            let key = [p.x.to_bits(), p.y.to_bits(), p.z.to_bits()];
+            *index_of.entry(key).or_insert_with(|| {
+                mesh.positions.push(p);
+                (mesh.positions.len() - 1) as u32
+            })
+        });
+        mesh.indices.push(tri);
+    }
+
+    Ok(mesh)
+}
+
+fn malformed(reason: &str) -> MeshError {
+    MeshError::Malformed {
+        format: "STL".into(),
+        reason: reason.into(),
+    }
+}
+
+/// Bytes looked into for the first facet of an ASCII file.
+const ASCII_PROBE: usize = 1024;
+
+/// Binary files may also start with "solid" in their header, only ASCII ones go on
+/// with a facet, or end right away when empty.
+fn is_ascii(bytes: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(&bytes[..bytes.len().min(ASCII_PROBE)]);
+    let mut words = text.split_whitespace();
+    words.next() == Some("solid") && words.any(|word| word == "facet" || word == "endsolid")
+}
+
+/// 80 bytes header, triangle count, then per triangle a normal, 3 corners and
+/// 2 attribute bytes.
+fn binary_triangles(bytes: &[u8]) -> anyhow::Result<Vec<[vec3; 3]>> {
+    if bytes.len() < 84 {
+        return Err(malformed("file is shorter than its header").into());
+    }
+    let count = u32::from_le_bytes(bytes[80..84].try_into()?) as usize;
+    let float =
+        |offset: usize| f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let point = |offset: usize| {
+        vec3::new(
+            float(offset) as f64,
+            float(offset + 4) as f64,
+            float(offset + 8) as f64,
+        )
+    };
+
+    if bytes.len() < 84 + count * 50 {
+        return Err(malformed("file is shorter than its triangle count").into());
+    }
+
+    Ok((0..count)
+        .map(|i| {
+            let offset = 84 + i * 50 + 12;
+            [point(offset), point(offset + 12), point(offset + 24)]
+        })
+        .collect())
+}
+
+fn ascii_triangles(text: &str) -> anyhow::Result<Vec<[vec3; 3]>> {
+    let mut triangles = Vec::new();
+    let mut corners = Vec::with_capacity(3);
+
+    for line in text.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("vertex") => {
+                let coords = words
+                    .map(str::parse::<f64>)
+                    .collect::<Result<Vec<_>, _>>()?;
+                if coords.len() != 3 {
+                    return Err(malformed("vertex should have 3 coordinates").into());
+                }
+                corners.push(vec3::new(coords[0], coords[1], coords[2]));
+            }
+            Some("endloop") => {
+                // polygons with more corners are split into a fan
+                for i in 1..corners.len().saturating_sub(1) {
+                    triangles.push([corners[0], corners[i], corners[i + 1]]);
+                }
+                corners.clear();
+            }
+            _ => {}
+        }
+    }
+
+    Ok(triangles)
+}
@@ -0,0 +1,98 @@
+use super::data::MeshData;
+use crate::{
+    entity::{
+        analytic::commons::{nearest_hit, ray_triangle, SurfacePoint},
+        bvh::Bvh,
+        traits::Entity,
+    },
+    helpers::types::vec2,
+    materials::material::Material,
+    math::{aabb::Aabb, interval::Interval, panics::PanickingNormalize},
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+use std::sync::Arc;
+
+/// Many triangles hit as one entity through its own BVH, sharing vertex buffers
+/// and one material per face group.
+#[derive(Debug)]
+pub struct TriangleMesh {
+    pub data: Arc<MeshData>,
+    /// Material of each face group.
+    pub materials: Vec<Material>,
+    /// Closed meshes are entered and left like solids, which dielectrics need.
+    /// Open meshes are two-sided.
+    pub closed: bool,
+    bvh: Bvh,
+}
+
+impl TriangleMesh {
+    /// ## PANICS if the mesh has no triangle or a face group has no material.
+    pub fn new(data: Arc<MeshData>, materials: Vec<Material>, closed: bool) -> Self {
+        if data.n_triangles() == 0 {
+            panic!("Mesh should have at least one triangle!");
+        }
+        if let Some(group) = (0..data.n_triangles())
+            .map(|i| data.group(i))
+            .find(|&group| group >= materials.len())
+        {
+            panic!("Face group {} has no material!", group);
+        }
+
+        let bounds: Vec<Aabb> = (0..data.n_triangles())
+            .map(|i| Aabb::from_points(data.vertices(i)))
+            .collect();
+
+        Self {
+            bvh: Bvh::new(&bounds),
+            data,
+            materials,
+            closed,
+        }
+    }
+}
+
+impl Entity for TriangleMesh {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        let tmin = interval.min().unwrap_or(f64::NEG_INFINITY);
+        let tmax = interval.max().unwrap_or(f64::INFINITY);
+
+        let (i, t, u, v) = self.bvh.closest(&ray, tmin, tmax, |i, tmax| {
+            let [a, b, c] = self.data.vertices(i);
+            let (t, u, v) = ray_triangle(&ray, a, b, c)?;
+            (interval.contains(t) && t < tmax).then_some((t, (i, t, u, v)))
+        })?;
+
+        let [a, b, c] = self.data.vertices(i);
+        let [ia, ib, ic] = self.data.indices[i].map(|v| v as usize);
+        let w = 1.0 - u - v;
+
+        let normal = match &self.data.normals {
+            Some(normals) => {
+                (w * normals[ia] + u * normals[ib] + v * normals[ic]).p_normalize()
+            }
+            None => (b - a).cross(&(c - a)).p_normalize(),
+        };
+        let uv = match &self.data.uvs {
+            Some(uvs) => w * uvs[ia] + u * uvs[ib] + v * uvs[ic],
+            None => vec2::new(u, v),
+        };
+
+        let point = SurfacePoint { t, normal, uv };
+        let mat = &self.materials[self.data.group(i)];
+        let hit = nearest_hit(ray, interval, &[point], mat, self.closed)?;
+
+        Some(match &self.data.colors {
+            Some(colors) => Hit {
+                material: hit
+                    .material
+                    .tinted(w * colors[ia] + u * colors[ib] + v * colors[ic]),
+                ..hit
+            },
+            None => hit,
+        })
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bvh.bounds()
+    }
+}
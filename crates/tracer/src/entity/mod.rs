@@ -1,6 +1,234 @@
 pub mod analytic;
 pub mod animated;
 pub mod animated_scene;
+pub mod atmosphere;
 pub mod backgrounds;
+pub mod bvh;
+pub mod csg;
+pub mod group;
+pub mod heightfield;
+pub mod instance;
+pub mod mesh;
 pub mod scene;
+pub mod sdf;
 pub mod traits;
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use super::{
+        analytic::{commons::ray_triangle, sphere::Sphere},
+        animated::physics::{PhysicsSettings, PhysicsWorld, RigidSphere, StaticPlane},
+        csg::{Csg, CsgOp},
+        instance::Instance,
+        mesh::{data::MeshData, triangle_mesh::TriangleMesh},
+        traits::Entity,
+    };
+    use crate::{
+        helpers::types::vec3,
+        materials::material::{Material, MaterialKind},
+        math::{interval::Interval, transform::Transform},
+        tracer::ray::{hit::Normal, ray::Ray},
+    };
+    use std::fs;
+
+    #[test]
+    fn test_instance_matches_world_sphere() {
+        let mat: Material = MaterialKind::Lambertian {
+            albedo: vec3::new(0.5, 0.5, 0.5),
+        }
+        .into();
+        let world = Sphere::new(vec3::new(4.0, 1.0, 0.0), 2.0, mat.clone());
+        let instance = Instance::new(
+            Arc::new(Sphere::new(vec3::zeros(), 1.0, mat)),
+            Transform::from_trs(
+                vec3::new(4.0, 1.0, 0.0),
+                vec3::new(0.0, 30.0, 0.0),
+                vec3::new(2.0, 2.0, 2.0),
+            ),
+        );
+
+        let ray = Ray::new(vec3::zeros(), vec3::new(1.0, 0.3, 0.1), 0.0);
+        let expected = world.hit_by(ray, Interval::GreaterThan(0.0)).unwrap();
+        let actual = instance.hit_by(ray, Interval::GreaterThan(0.0)).unwrap();
+
+        assert!((expected.t - actual.t).abs() < 1e-9);
+        assert!((expected.pos - actual.pos).magnitude() < 1e-9);
+        match (expected.normal, actual.normal) {
+            (Normal::Outward(n1), Normal::Outward(n2)) => assert!((n1 - n2).magnitude() < 1e-9),
+            _ => panic!("Both hits should be outward!"),
+        }
+    }
+
+    #[test]
+    fn test_csg_hollow_sphere() {
+        let mat: Material = MaterialKind::Dielectric { eta: 1.5 }.into();
+        let hollow = Csg::new(
+            CsgOp::Difference,
+            Arc::new(Sphere::new(vec3::zeros(), 2.0, mat.clone())),
+            Arc::new(Sphere::new(vec3::zeros(), 1.0, mat)),
+        );
+        let ray = Ray::new(vec3::new(-5.0, 0.0, 0.0), vec3::new(1.0, 0.0, 0.0), 0.0);
+
+        // (t, entering the solid)
+        let expected = [(3.0, true), (4.0, false), (6.0, true), (7.0, false)];
+        let mut tmin = 0.0;
+        for (t, entering) in expected {
+            let hit = hollow.hit_by(ray, Interval::GreaterThan(tmin)).unwrap();
+            assert!((hit.t - t).abs() < 1e-9);
+            match hit.normal {
+                Normal::Outward(n) => {
+                    assert!(entering);
+                    assert!(n.dot(&ray.dir) < 0.0);
+                }
+                Normal::Inward(n) => {
+                    assert!(!entering);
+                    assert!(n.dot(&ray.dir) < 0.0);
+                }
+            }
+            tmin = hit.t + 1e-4;
+        }
+        assert!(hollow.hit_by(ray, Interval::GreaterThan(tmin)).is_none());
+    }
+
+    #[test]
+    fn test_ply_matches_stl() {
+        let dir = std::env::temp_dir();
+        let ply_path = dir.join("raytrace_test_quad.ply");
+        let stl_path = dir.join("raytrace_test_quad.stl");
+
+        // a unit quad in the xy plane, as one polygon with vertex colors
+        let ply = "ply\nformat ascii 1.0\nelement vertex 4\n\
+            property float x\nproperty float y\nproperty float z\n\
+            property uchar red\nproperty uchar green\nproperty uchar blue\n\
+            element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+            0 0 0 255 0 0\n1 0 0 255 0 0\n1 1 0 255 0 0\n0 1 0 255 0 0\n4 0 1 2 3\n";
+        fs::write(&ply_path, ply).unwrap();
+
+        // the same quad as two binary STL facets, sharing two corners,
+        // with a header starting like an ASCII file
+        let corners = [
+            [0.0f32, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let mut stl = b"solid quad".to_vec();
+        stl.resize(80, 0);
+        stl.extend(2u32.to_le_bytes());
+        for tri in [[0, 1, 2], [0, 2, 3]] {
+            stl.extend([0.0f32, 0.0, 1.0].iter().flat_map(|x| x.to_le_bytes()));
+            for i in tri {
+                stl.extend(corners[i].iter().flat_map(|x| x.to_le_bytes()));
+            }
+            stl.extend([0u8; 2]);
+        }
+        fs::write(&stl_path, stl).unwrap();
+
+        let ply = MeshData::load(ply_path.to_str().unwrap()).unwrap();
+        let stl = MeshData::load(stl_path.to_str().unwrap()).unwrap();
+        fs::remove_file(&ply_path).unwrap();
+        fs::remove_file(&stl_path).unwrap();
+        assert_eq!((ply.positions.len(), ply.n_triangles()), (4, 2));
+        assert_eq!((stl.positions.len(), stl.n_triangles()), (4, 2));
+        assert_eq!(ply.colors.as_ref().unwrap()[2], vec3::new(1.0, 0.0, 0.0));
+
+        let mat = Material {
+            id: 3,
+            kind: MaterialKind::Lambertian {
+                albedo: vec3::new(0.5, 0.5, 0.5),
+            },
+        };
+        let ray = Ray::new(vec3::new(0.7, 0.2, 3.0), vec3::new(0.0, 0.0, -1.0), 0.0);
+        for data in [ply, stl] {
+            let mesh = TriangleMesh::new(Arc::new(data), vec![mat.clone()], false);
+            let hit = mesh.hit_by(ray, Interval::GreaterThan(0.0)).unwrap();
+            assert!((hit.t - 3.0).abs() < 1e-9);
+            assert_eq!(hit.material_id, 3);
+        }
+    }
+
+    #[test]
+    fn test_mesh_bvh_matches_brute_force() {
+        // a bumpy grid of 2 * 40 * 40 triangles
+        let n = 40;
+        let positions = (0..=n)
+            .flat_map(|i| (0..=n).map(move |j| (i as f64, j as f64)))
+            .map(|(x, z)| vec3::new(x, (0.7 * x).sin() * (0.4 * z).cos(), z))
+            .collect();
+        let indices = (0..n)
+            .flat_map(|i| (0..n).map(move |j| i * (n + 1) + j))
+            .flat_map(|v| [[v, v + 1, v + n + 1], [v + 1, v + n + 2, v + n + 1]])
+            .collect();
+        let data = Arc::new(MeshData {
+            positions,
+            indices,
+            ..Default::default()
+        });
+
+        let mat: Material = MaterialKind::Lambertian {
+            albedo: vec3::new(0.5, 0.5, 0.5),
+        }
+        .into();
+        let mesh = TriangleMesh::new(data.clone(), vec![mat], false);
+
+        for k in 0..200 {
+            let k = k as f64;
+            let orig = vec3::new(-5.0 + 0.3 * k, 6.0, -3.0 + 0.17 * k);
+            let ray = Ray::new(
+                orig,
+                vec3::new((0.1 * k).cos(), -1.0, (0.13 * k).sin()),
+                0.0,
+            );
+
+            let expected = (0..data.n_triangles())
+                .filter_map(|i| {
+                    let [a, b, c] = data.vertices(i);
+                    ray_triangle(&ray, a, b, c).map(|(t, _, _)| t)
+                })
+                .filter(|&t| t > 0.0)
+                .min_by(f64::total_cmp);
+            let actual = mesh
+                .hit_by(ray, Interval::GreaterThan(0.0))
+                .map(|hit| hit.t);
+
+            match (expected, actual) {
+                (Some(t1), Some(t2)) => assert!((t1 - t2).abs() < 1e-9),
+                (None, None) => {}
+                _ => panic!("BVH and brute force disagree: {:?} {:?}", expected, actual),
+            }
+        }
+    }
+
+    #[test]
+    fn test_physics_bounce() {
+        let mut world = PhysicsWorld::new(PhysicsSettings {
+            gravity: vec3::new(0.0, -10.0, 0.0),
+            duration: 4.0,
+            time_step: 1e-3,
+        });
+        world.planes.push(StaticPlane {
+            point: vec3::zeros(),
+            normal: vec3::new(0.0, 1.0, 0.0),
+        });
+        world.bodies.push(RigidSphere {
+            center: vec3::new(0.0, 5.0, 0.0),
+            radius: 1.0,
+            mass: 1.0,
+            velocity: vec3::zeros(),
+            restitution: 0.5,
+        });
+
+        let trajectory = &world.simulate()[0];
+        let heights: Vec<f64> = trajectory.keys().iter().map(|key| key.value.y).collect();
+        assert!(heights.iter().all(|&y| y >= 1.0 - 1e-9));
+
+        // falls 4 to the ground in ~0.894, rises back to a quarter of it
+        let peak = heights[1000..1800].iter().copied().fold(f64::MIN, f64::max);
+        assert!((peak - 2.0).abs() < 0.05, "peak {}", peak);
+
+        // comes to rest on the ground
+        assert!((trajectory.sample(4.0).y - 1.0).abs() < 1e-3);
+    }
+}
@@ -1,14 +1,16 @@
-use super::{backgrounds::Background, traits::Entity};
+use super::{atmosphere::Atmosphere, backgrounds::Background, traits::Entity};
 use crate::{
     math::interval::Interval,
     tracer::ray::{hit::Hit, ray::Ray},
 };
+use rand::rngs::ThreadRng;
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct Scene {
     pub entities: Vec<Arc<dyn Entity>>,
     pub background: Background,
+    pub atmosphere: Option<Atmosphere>,
 }
 
 impl Scene {
@@ -16,24 +18,39 @@ impl Scene {
         Self {
             entities,
             background,
+            atmosphere: None,
         }
     }
 }
 
 impl Scene {
     pub fn hit_by(&self, ray: Ray) -> Option<Hit> {
+        self.hit_entity(ray).map(|(_, hit)| hit)
+    }
+
+    /// Nearest hit along the ray, with the index of the entity hit in `entities`.
+    pub fn hit_entity(&self, ray: Ray) -> Option<(usize, Hit)> {
         let mut interval = Interval::GreaterThan(ray.tmin);
 
         let mut scene_hit = None;
 
         // clone the arcs
-        for ent in self.entities.clone() {
+        for (i, ent) in self.entities.clone().into_iter().enumerate() {
             if let Some(hit) = ent.hit_by(ray, interval) {
                 interval = interval.clamp_high(hit.t);
-                scene_hit = Some(hit);
+                scene_hit = Some((i, hit));
             }
         }
 
         scene_hit
     }
+
+    /// Nearest hit along the ray, taking scattering inside the atmosphere into account.
+    pub fn hit_through_atmosphere(&self, ray: Ray, rng: &mut ThreadRng) -> Option<Hit> {
+        let hit = self.hit_by(ray);
+        match &self.atmosphere {
+            Some(atmosphere) => atmosphere.scatter_before(ray, hit, rng),
+            None => hit,
+        }
+    }
 }
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helpers::types::{vec2, vec3},
+    math::aabb::Aabb,
+};
+
+fn default_power() -> f64 {
+    8.0
+}
+
+fn default_iterations() -> u32 {
+    12
+}
+
+/// A signed distance field, negative inside.
+/// Every primitive is centered on the origin unless stated otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SdfNode {
+    Sphere {
+        radius: f64,
+    },
+    Box {
+        half_size: vec3,
+        /// Radius of the rounded edges, grows the box.
+        #[serde(default)]
+        rounding: f64,
+    },
+    /// Lies in the xz plane.
+    Torus {
+        major_radius: f64,
+        minor_radius: f64,
+    },
+    Capsule {
+        a: vec3,
+        b: vec3,
+        radius: f64,
+    },
+    /// Power 8 gives the classic bulb, fits in a sphere of radius 1.2.
+    Mandelbulb {
+        #[serde(default = "default_power")]
+        power: f64,
+        #[serde(default = "default_iterations")]
+        iterations: u32,
+    },
+
+    Translate {
+        offset: vec3,
+        node: Box<SdfNode>,
+    },
+    /// Rotates around the y axis by `rate` radians per unit of height.
+    Twist {
+        rate: f64,
+        node: Box<SdfNode>,
+    },
+    /// Infinite copies of `node` every `period`, a 0 component does not repeat that axis.
+    Repeat {
+        period: vec3,
+        node: Box<SdfNode>,
+    },
+
+    Union {
+        a: Box<SdfNode>,
+        b: Box<SdfNode>,
+    },
+    Intersection {
+        a: Box<SdfNode>,
+        b: Box<SdfNode>,
+    },
+    /// `a` minus `b`.
+    Difference {
+        a: Box<SdfNode>,
+        b: Box<SdfNode>,
+    },
+    /// - `k`: size of the blended region.
+    SmoothUnion {
+        k: f64,
+        a: Box<SdfNode>,
+        b: Box<SdfNode>,
+    },
+    SmoothIntersection {
+        k: f64,
+        a: Box<SdfNode>,
+        b: Box<SdfNode>,
+    },
+    SmoothDifference {
+        k: f64,
+        a: Box<SdfNode>,
+        b: Box<SdfNode>,
+    },
+}
+
+impl SdfNode {
+    pub fn distance(&self, p: vec3) -> f64 {
+        match self {
+            SdfNode::Sphere { radius } => p.norm() - radius,
+            SdfNode::Box {
+                half_size,
+                rounding,
+            } => {
+                let q = p.abs() - half_size;
+                q.sup(&vec3::zeros()).norm() + q.max().min(0.0) - rounding
+            }
+            SdfNode::Torus {
+                major_radius,
+                minor_radius,
+            } => {
+                let q = vec2::new(vec2::new(p.x, p.z).norm() - major_radius, p.y);
+                q.norm() - minor_radius
+            }
+            SdfNode::Capsule { a, b, radius } => {
+                let pa = p - a;
+                let ba = b - a;
+                let h = (pa.dot(&ba) / ba.norm_squared()).clamp(0.0, 1.0);
+                (pa - ba * h).norm() - radius
+            }
+            SdfNode::Mandelbulb { power, iterations } => mandelbulb(p, *power, *iterations),
+
+            SdfNode::Translate { offset, node } => node.distance(p - offset),
+            SdfNode::Twist { rate, node } => {
+                let (s, c) = (rate * p.y).sin_cos();
+                node.distance(vec3::new(c * p.x - s * p.z, p.y, s * p.x + c * p.z))
+            }
+            SdfNode::Repeat { period, node } => {
+                let q = p.zip_map(period, |x, period| {
+                    if period == 0.0 {
+                        x
+                    } else {
+                        x - period * (x / period).round()
+                    }
+                });
+                node.distance(q)
+            }
+
+            SdfNode::Union { a, b } => a.distance(p).min(b.distance(p)),
+            SdfNode::Intersection { a, b } => a.distance(p).max(b.distance(p)),
+            SdfNode::Difference { a, b } => a.distance(p).max(-b.distance(p)),
+            SdfNode::SmoothUnion { k, a, b } => {
+                let (da, db) = (a.distance(p), b.distance(p));
+                let h = (0.5 + 0.5 * (db - da) / k).clamp(0.0, 1.0);
+                mix(db, da, h) - k * h * (1.0 - h)
+            }
+            SdfNode::SmoothIntersection { k, a, b } => {
+                let (da, db) = (a.distance(p), b.distance(p));
+                let h = (0.5 - 0.5 * (db - da) / k).clamp(0.0, 1.0);
+                mix(db, da, h) + k * h * (1.0 - h)
+            }
+            SdfNode::SmoothDifference { k, a, b } => {
+                let (da, db) = (a.distance(p), b.distance(p));
+                let h = (0.5 - 0.5 * (da + db) / k).clamp(0.0, 1.0);
+                mix(da, -db, h) + k * h * (1.0 - h)
+            }
+        }
+    }
+
+    /// `None` if the field extends infinitely, e.g. repetitions.
+    pub fn bounds(&self) -> Option<Aabb> {
+        let cube = |r: f64| Aabb::new(vec3::new(-r, -r, -r), vec3::new(r, r, r));
+        match self {
+            SdfNode::Sphere { radius } => Some(cube(*radius)),
+            SdfNode::Box {
+                half_size,
+                rounding,
+            } => Some(Aabb::new(-half_size, *half_size).padded(*rounding)),
+            SdfNode::Torus {
+                major_radius,
+                minor_radius,
+            } => {
+                let r = major_radius + minor_radius;
+                let half_size = vec3::new(r, *minor_radius, r);
+                Some(Aabb::new(-half_size, half_size))
+            }
+            SdfNode::Capsule { a, b, radius } => {
+                Some(Aabb::from_points([*a, *b]).padded(*radius))
+            }
+            SdfNode::Mandelbulb { .. } => Some(cube(1.2)),
+
+            SdfNode::Translate { offset, node } => node.bounds().map(|aabb| Aabb {
+                min: aabb.min + offset,
+                max: aabb.max + offset,
+            }),
+            SdfNode::Twist { node, .. } => node.bounds().map(|aabb| {
+                // any rotation around y stays within this radius
+                let r = aabb
+                    .corners()
+                    .iter()
+                    .map(|c| vec2::new(c.x, c.z).norm())
+                    .fold(0.0, f64::max);
+                Aabb::new(vec3::new(-r, aabb.min.y, -r), vec3::new(r, aabb.max.y, r))
+            }),
+            SdfNode::Repeat { .. } => None,
+
+            SdfNode::Union { a, b } => Some(a.bounds()?.union(&b.bounds()?)),
+            SdfNode::Intersection { a, b } => Some(a.bounds()?.intersection(&b.bounds()?)),
+            SdfNode::Difference { a, .. } => a.bounds(),
+            SdfNode::SmoothUnion { k, a, b } => {
+                Some(a.bounds()?.union(&b.bounds()?).padded(*k))
+            }
+            SdfNode::SmoothIntersection { a, b, .. } => {
+                Some(a.bounds()?.intersection(&b.bounds()?))
+            }
+            SdfNode::SmoothDifference { a, .. } => a.bounds(),
+        }
+    }
+}
+
+#[inline]
+fn mix(x: f64, y: f64, a: f64) -> f64 {
+    x * (1.0 - a) + y * a
+}
+
+/// Distance estimate from the running derivative of the power iteration.
+fn mandelbulb(p: vec3, power: f64, iterations: u32) -> f64 {
+    let mut z = p;
+    let mut dr = 1.0;
+    let mut r = z.norm();
+
+    for _ in 0..iterations {
+        if r > 2.0 || r <= f64::EPSILON {
+            break;
+        }
+
+        let theta = (z.z / r).acos() * power;
+        let phi = f64::atan2(z.y, z.x) * power;
+        dr = r.powf(power - 1.0) * power * dr + 1.0;
+
+        z = r.powf(power)
+            * vec3::new(
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            )
+            + p;
+        r = z.norm();
+    }
+
+    if r <= f64::EPSILON {
+        // deep inside the set
+        return -f64::EPSILON;
+    }
+    0.5 * r.ln() * r / dr
+}
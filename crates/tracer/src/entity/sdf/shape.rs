@@ -0,0 +1,108 @@
+use super::node::SdfNode;
+use crate::{
+    entity::{
+        analytic::commons::{nearest_hit, SurfacePoint},
+        traits::Entity,
+    },
+    helpers::types::{vec2, vec3},
+    materials::material::Material,
+    math::{aabb::Aabb, interval::Interval},
+    tracer::ray::{hit::Hit, ray::Ray},
+};
+
+/// A signed distance field rendered by sphere tracing.
+#[derive(Debug)]
+pub struct SdfShape {
+    pub node: SdfNode,
+    /// Rays are only marched inside these bounds.
+    pub bounds: Aabb,
+    pub mat: Material,
+    pub max_steps: u32,
+    /// A point closer than this to the surface is on it.
+    pub epsilon: f64,
+    /// Fraction of the distance to advance each step,
+    /// below 1 for fields that overestimate distances, e.g. twists.
+    pub step_scale: f64,
+}
+
+impl SdfShape {
+    /// ## PANICS if `bounds` is `None` and the field is unbounded.
+    pub fn new(node: SdfNode, bounds: Option<Aabb>, mat: Material) -> Self {
+        let bounds = bounds
+            .or_else(|| node.bounds())
+            .expect("An unbounded distance field needs explicit bounds!");
+        Self {
+            node,
+            bounds,
+            mat,
+            max_steps: 256,
+            epsilon: 1e-4,
+            step_scale: 1.0,
+        }
+    }
+
+    /// Central differences of the field.
+    fn normal_at(&self, p: vec3, fallback: vec3) -> vec3 {
+        let h = self.epsilon;
+        let d = |offset: vec3| self.node.distance(p + offset) - self.node.distance(p - offset);
+        let grad = vec3::new(
+            d(vec3::new(h, 0.0, 0.0)),
+            d(vec3::new(0.0, h, 0.0)),
+            d(vec3::new(0.0, 0.0, h)),
+        );
+        let norm = grad.norm();
+        if norm > f64::EPSILON {
+            grad / norm
+        } else {
+            fallback
+        }
+    }
+}
+
+impl Entity for SdfShape {
+    fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit> {
+        let (tnear, tfar) = self.bounds.clip(&ray)?;
+        let tmin = tnear.max(interval.min().unwrap_or(f64::NEG_INFINITY));
+        let tmax = tfar.min(interval.max().unwrap_or(f64::INFINITY));
+        if tmin >= tmax {
+            return None;
+        }
+
+        let mut t = tmin;
+        let mut distance = self.node.distance(ray.at(t));
+
+        // a ray leaving the surface starts on it, step off before deciding the side
+        let mut nudges = 0;
+        while distance.abs() < self.epsilon && nudges < 16 {
+            t += 2.0 * self.epsilon;
+            distance = self.node.distance(ray.at(t));
+            nudges += 1;
+        }
+        // march towards the surface from whichever side we are on
+        let sign = distance.signum();
+
+        for _ in 0..self.max_steps {
+            if t > tmax {
+                return None;
+            }
+            let d = sign * self.node.distance(ray.at(t));
+            if d < self.epsilon {
+                let pos = ray.at(t);
+                let uv = (pos - self.bounds.min).component_div(&self.bounds.size());
+                let point = SurfacePoint {
+                    t,
+                    normal: self.normal_at(pos, -sign * ray.dir),
+                    uv: vec2::new(uv.x, uv.z),
+                };
+                return nearest_hit(ray, Interval::Unbounded, &[point], &self.mat, true);
+            }
+            t += self.step_scale * d;
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
@@ -1,13 +1,25 @@
+use super::csg::Span;
 use crate::{
-    math::interval::Interval,
+    math::{aabb::Aabb, interval::Interval},
     tracer::ray::{hit::Hit, ray::Ray},
 };
 use std::{fmt::Debug, sync::Arc};
 
 pub trait Entity: Sync + Send + Debug {
     fn hit_by(&self, ray: Ray, interval: Interval) -> Option<Hit>;
+
+    /// `None` if the entity is unbounded, e.g. a plane.
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
 }
 
 pub trait AnimatedEntity: Entity {
     fn step(&self, t: f64) -> Arc<dyn AnimatedEntity>;
 }
+
+/// A watertight entity that can report every part of a ray's line lying inside it.
+pub trait Solid: Entity {
+    /// Sorted, disjoint spans along the whole line of `ray`, ignoring `ray.tmin`.
+    fn spans(&self, ray: Ray) -> Vec<Span>;
+}
@@ -4,6 +4,7 @@ pub type vec3 = vec<3>;
 
 pub type mat<const M: usize, const N: usize> = nalgebra::SMatrix<f64, M, N>;
 pub type mat3 = mat<3, 3>;
+pub type mat4 = mat<4, 4>;
 
 pub type color = vec<3>;
 
@@ -26,9 +26,26 @@ impl TextureMap {
     }
 }
 
+/// Material of an entity, with its place in the material table.
+#[derive(Debug, Clone, Serialize)]
+pub struct Material {
+    /// Position in the `[[materials]]` table, from 1.
+    /// 0 for materials not from the table, e.g. imported with a glTF file.
+    #[serde(skip)]
+    pub id: u32,
+    #[serde(flatten)]
+    pub kind: MaterialKind,
+}
+
+impl From<MaterialKind> for Material {
+    fn from(kind: MaterialKind) -> Self {
+        Self { id: 0, kind }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type")]
-pub enum Material {
+pub enum MaterialKind {
     Lambertian {
         albedo: color,
     },
@@ -85,28 +102,71 @@ pub enum FragMaterial {
         color: color,
     },
     Smoke,
+    /// Scatters uniformly in every direction, used by participating media.
+    Isotropic {
+        albedo: color,
+    },
 }
 
 impl TryFrom<Material> for FragMaterial {
     type Error = MaterialError;
 
     fn try_from(value: Material) -> Result<Self, Self::Error> {
-        match value {
-            Material::Lambertian { albedo } => Ok(FragMaterial::Lambertian { albedo }),
-            Material::Metal { albedo } => Ok(FragMaterial::Metal { albedo }),
-            Material::FuzzedMetal { albedo, fuzz } => {
+        match value.kind {
+            MaterialKind::Lambertian { albedo } => Ok(FragMaterial::Lambertian { albedo }),
+            MaterialKind::Metal { albedo } => Ok(FragMaterial::Metal { albedo }),
+            MaterialKind::FuzzedMetal { albedo, fuzz } => {
                 Ok(FragMaterial::FuzzedMetal { albedo, fuzz })
             }
-            Material::Dielectric { eta } => Ok(FragMaterial::Dielectric { eta }),
+            MaterialKind::Dielectric { eta } => Ok(FragMaterial::Dielectric { eta }),
 
-            Material::DiffuseLight { color } => Ok(FragMaterial::DiffuseLight { color: color }),
-            Material::PolarChecker { .. } => Err(MaterialError::CannotConvert {
+            MaterialKind::DiffuseLight { color } => {
+                Ok(FragMaterial::DiffuseLight { color: color })
+            }
+            MaterialKind::PolarChecker { .. } => Err(MaterialError::CannotConvert {
                 mat_type: "polar checker".into(),
             }),
-            Material::Texture { .. } => Err(MaterialError::CannotConvert {
+            MaterialKind::Texture { .. } => Err(MaterialError::CannotConvert {
                 mat_type: "texture".into(),
             }),
-            Material::Smoke { .. } => Ok(FragMaterial::Smoke),
+            MaterialKind::Smoke { .. } => Ok(FragMaterial::Smoke),
+        }
+    }
+}
+
+impl FragMaterial {
+    /// Multiplies the albedo by `tint`, e.g. a vertex color.
+    /// Materials without an albedo are unchanged.
+    pub fn tinted(self, tint: color) -> Self {
+        match self {
+            FragMaterial::Lambertian { albedo } => FragMaterial::Lambertian {
+                albedo: albedo.component_mul(&tint),
+            },
+            FragMaterial::Metal { albedo } => FragMaterial::Metal {
+                albedo: albedo.component_mul(&tint),
+            },
+            FragMaterial::FuzzedMetal { albedo, fuzz } => FragMaterial::FuzzedMetal {
+                albedo: albedo.component_mul(&tint),
+                fuzz,
+            },
+            FragMaterial::Isotropic { albedo } => FragMaterial::Isotropic {
+                albedo: albedo.component_mul(&tint),
+            },
+            other => other,
+        }
+    }
+
+    /// Color reflected under white light, white for clear materials and black for lights.
+    pub fn albedo(&self) -> color {
+        match self {
+            FragMaterial::Lambertian { albedo }
+            | FragMaterial::Metal { albedo }
+            | FragMaterial::FuzzedMetal { albedo, .. }
+            | FragMaterial::Isotropic { albedo } => *albedo,
+            FragMaterial::Dielectric { .. } | FragMaterial::Transparent => {
+                color::new(1.0, 1.0, 1.0)
+            }
+            FragMaterial::DiffuseLight { .. } | FragMaterial::Smoke => color::zeros(),
         }
     }
 }
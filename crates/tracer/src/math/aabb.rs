@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{helpers::types::vec3, tracer::ray::ray::Ray};
+
+use super::transform::Transform;
+
+/// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Aabb {
+    pub min: vec3,
+    pub max: vec3,
+}
+
+impl Aabb {
+    pub fn new(min: vec3, max: vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// ## PANICS if `points` is empty.
+    pub fn from_points(points: impl IntoIterator<Item = vec3>) -> Self {
+        let mut points = points.into_iter();
+        let first = points
+            .next()
+            .expect("Bounding box needs at least one point!");
+        points.fold(Self::new(first, first), |aabb, p| Self {
+            min: aabb.min.inf(&p),
+            max: aabb.max.sup(&p),
+        })
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.sup(&other.min),
+            max: self.max.inf(&other.max),
+        }
+    }
+
+    pub fn padded(&self, pad: f64) -> Self {
+        let pad = vec3::new(pad, pad, pad);
+        Self {
+            min: self.min - pad,
+            max: self.max + pad,
+        }
+    }
+
+    pub fn center(&self) -> vec3 {
+        0.5 * (self.min + self.max)
+    }
+
+    pub fn size(&self) -> vec3 {
+        self.max - self.min
+    }
+
+    pub fn corners(&self) -> [vec3; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            vec3::new(a.x, a.y, a.z),
+            vec3::new(b.x, a.y, a.z),
+            vec3::new(a.x, b.y, a.z),
+            vec3::new(b.x, b.y, a.z),
+            vec3::new(a.x, a.y, b.z),
+            vec3::new(b.x, a.y, b.z),
+            vec3::new(a.x, b.y, b.z),
+            vec3::new(b.x, b.y, b.z),
+        ]
+    }
+
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        Self::from_points(self.corners().map(|p| transform.point(p)))
+    }
+
+    /// Entry and exit `t` of the whole line of `ray`, if it passes through the box.
+    pub fn clip(&self, ray: &Ray) -> Option<(f64, f64)> {
+        let mut tnear = f64::NEG_INFINITY;
+        let mut tfar = f64::INFINITY;
+
+        for axis in 0..3 {
+            let inv = 1.0 / ray.dir[axis];
+            let t0 = (self.min[axis] - ray.orig[axis]) * inv;
+            let t1 = (self.max[axis] - ray.orig[axis]) * inv;
+            tnear = tnear.max(t0.min(t1));
+            tfar = tfar.min(t0.max(t1));
+        }
+
+        if tnear <= tfar {
+            Some((tnear, tfar))
+        } else {
+            None
+        }
+    }
+}
@@ -3,3 +3,7 @@ use std::f64::consts::PI;
 pub fn deg2rad(deg: f64) -> f64 {
     deg / 180.0 * PI
 }
+
+pub fn rad2deg(rad: f64) -> f64 {
+    rad / PI * 180.0
+}
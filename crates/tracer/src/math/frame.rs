@@ -0,0 +1,47 @@
+use crate::helpers::types::vec3;
+
+use super::{aabb::Aabb, panics::PanickingNormalize};
+
+/// An orthonormal frame whose local z axis is `w`.
+#[derive(Debug, Clone, Copy)]
+pub struct Frame {
+    pub origin: vec3,
+    pub u: vec3,
+    pub v: vec3,
+    pub w: vec3,
+}
+
+impl Frame {
+    pub fn new(origin: vec3, axis: vec3) -> Self {
+        let w = axis.p_normalize();
+        let helper = if w.x.abs() > 0.9 {
+            vec3::y()
+        } else {
+            vec3::x()
+        };
+        let u = w.cross(&helper).p_normalize();
+        let v = w.cross(&u);
+        Self { origin, u, v, w }
+    }
+
+    pub fn to_local(&self, p: vec3) -> vec3 {
+        self.vector_to_local(p - self.origin)
+    }
+
+    pub fn vector_to_local(&self, d: vec3) -> vec3 {
+        vec3::new(d.dot(&self.u), d.dot(&self.v), d.dot(&self.w))
+    }
+
+    pub fn to_world(&self, p: vec3) -> vec3 {
+        self.origin + self.vector_to_world(p)
+    }
+
+    pub fn vector_to_world(&self, d: vec3) -> vec3 {
+        self.u * d.x + self.v * d.y + self.w * d.z
+    }
+
+    /// World bounding box of a box given in local coords.
+    pub fn aabb_to_world(&self, local: &Aabb) -> Aabb {
+        Aabb::from_points(local.corners().map(|p| self.to_world(p)))
+    }
+}
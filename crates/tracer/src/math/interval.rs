@@ -84,4 +84,26 @@ impl Interval {
             Interval::Unbounded => None,
         }
     }
+
+    pub fn max(&self) -> Option<f64> {
+        match self {
+            Interval::Between { low: _, high } => Some(*high),
+            Interval::LessThan(high) => Some(*high),
+            Interval::GreaterThan(_) => None,
+            Interval::Unbounded => None,
+        }
+    }
+
+    /// Scales both bounds by a positive factor.
+    pub fn scaled(self, s: f64) -> Self {
+        match self {
+            Interval::Between { low, high } => Interval::Between {
+                low: low * s,
+                high: high * s,
+            },
+            Interval::LessThan(high) => Interval::LessThan(high * s),
+            Interval::GreaterThan(low) => Interval::GreaterThan(low * s),
+            Interval::Unbounded => Interval::Unbounded,
+        }
+    }
 }
@@ -0,0 +1,166 @@
+use crate::helpers::types::vec2;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Mul, Sub};
+
+/// How a value travels from a keyframe to the next.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    /// Cubic curve through the keyframes, tangents taken from the neighbours.
+    Bezier,
+    /// Holds the value until the next keyframe.
+    Step,
+}
+
+/// Remaps the progress between two keyframes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// `s` in \[0, 1\].
+    pub fn apply(self, s: f64) -> f64 {
+        match self {
+            Easing::Linear => s,
+            Easing::EaseIn => s * s * s,
+            Easing::EaseOut => 1.0 - (1.0 - s).powi(3),
+            Easing::EaseInOut => {
+                if s < 0.5 {
+                    4.0 * s * s * s
+                } else {
+                    1.0 - (2.0 - 2.0 * s).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// `interpolation` and `easing` apply to the segment starting at this keyframe.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Keyframe<T> {
+    pub time: f64,
+    pub value: T,
+    #[serde(default)]
+    pub interpolation: Interpolation,
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+/// Values that can be blended, e.g. `f64` and vectors.
+pub trait Animatable:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Mul<f64, Output = Self>
+{
+}
+
+impl<T> Animatable for T where T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f64, Output = T>
+{}
+
+/// How tracks are written in TOML: either a list of keyframes, or the control points
+/// of a Catmull-Rom spline passed through at even times over `time`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TrackConfig<T> {
+    Keys(Vec<Keyframe<T>>),
+    Spline { points: Vec<T>, time: vec2 },
+}
+
+/// A value changing over animation time, held constant before the first
+/// and after the last keyframe.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    try_from = "TrackConfig<T>",
+    into = "TrackConfig<T>",
+    bound = "T: Animatable + Serialize + for<'a> Deserialize<'a>"
+)]
+pub struct Track<T> {
+    keys: Vec<Keyframe<T>>,
+}
+
+impl<T: Animatable> Track<T> {
+    /// ## PANICS if `keys` is empty.
+    pub fn new(mut keys: Vec<Keyframe<T>>) -> Self {
+        if keys.is_empty() {
+            panic!("Track should have at least one keyframe!");
+        }
+        keys.sort_by(|k1, k2| k1.time.total_cmp(&k2.time));
+        Self { keys }
+    }
+
+    pub fn sample(&self, time: f64) -> T {
+        let next = self.keys.partition_point(|key| key.time <= time);
+        if next == 0 {
+            return self.keys[0].value;
+        }
+        if next == self.keys.len() {
+            return self.keys[next - 1].value;
+        }
+
+        let (k0, k1) = (&self.keys[next - 1], &self.keys[next]);
+        let s = k0.easing.apply((time - k0.time) / (k1.time - k0.time));
+
+        match k0.interpolation {
+            Interpolation::Step => k0.value,
+            Interpolation::Linear => k0.value + (k1.value - k0.value) * s,
+            Interpolation::Bezier => {
+                let prev = if next >= 2 {
+                    self.keys[next - 2].value
+                } else {
+                    k0.value
+                };
+                let after = self.keys.get(next + 1).map_or(k1.value, |key| key.value);
+                // Catmull-Rom tangents as Bezier control points
+                let c0 = k0.value + (k1.value - prev) * (1.0 / 6.0);
+                let c1 = k1.value - (after - k0.value) * (1.0 / 6.0);
+
+                let r = 1.0 - s;
+                k0.value * (r * r * r)
+                    + c0 * (3.0 * r * r * s)
+                    + c1 * (3.0 * r * s * s)
+                    + k1.value * (s * s * s)
+            }
+        }
+    }
+
+    pub fn keys(&self) -> &[Keyframe<T>] {
+        &self.keys
+    }
+}
+
+impl<T: Animatable> TryFrom<TrackConfig<T>> for Track<T> {
+    type Error = String;
+
+    fn try_from(config: TrackConfig<T>) -> Result<Self, Self::Error> {
+        let keys = match config {
+            TrackConfig::Keys(keys) => keys,
+            TrackConfig::Spline { points, time } => {
+                let step = (time.y - time.x) / (points.len().max(2) - 1) as f64;
+                points
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, value)| Keyframe {
+                        time: time.x + step * i as f64,
+                        value,
+                        interpolation: Interpolation::Bezier,
+                        easing: Easing::Linear,
+                    })
+                    .collect()
+            }
+        };
+        if keys.is_empty() {
+            return Err("Track should have at least one keyframe!".into());
+        }
+        Ok(Self::new(keys))
+    }
+}
+
+impl<T> From<Track<T>> for TrackConfig<T> {
+    fn from(track: Track<T>) -> Self {
+        TrackConfig::Keys(track.keys)
+    }
+}
@@ -1,8 +1,61 @@
 #![allow(clippy::disallowed_methods)]
 
+pub mod aabb;
+pub mod angles;
+pub mod distributions;
+pub mod frame;
 pub mod interval;
+pub mod keyframes;
 pub mod panics;
-pub mod distributions;
+pub mod polynomial;
 pub mod ray;
-pub mod angles;
+pub mod transform;
+
+#[cfg(test)]
+pub mod tests {
+    use super::{
+        keyframes::{Easing, Interpolation, Keyframe, Track},
+        polynomial::solve_quartic,
+    };
+
+    #[test]
+    fn test_quartic_roots() {
+        // (x + 2)(x - 0.5)(x - 1)(x - 3)
+        let roots = solve_quartic(1.0, -2.5, -4.0, 8.5, -3.0);
+        let expected = [-2.0, 0.5, 1.0, 3.0];
+        assert_eq!(roots.len(), expected.len());
+        for (root, expected) in roots.iter().zip(expected) {
+            assert!((root - expected).abs() < 1e-9);
+        }
+
+        // x^4 + 1 has no real roots
+        assert!(solve_quartic(1.0, 0.0, 0.0, 0.0, 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_keyframe_track() {
+        let key = |time, value, interpolation| Keyframe {
+            time,
+            value,
+            interpolation,
+            easing: Easing::Linear,
+        };
+        let track = Track::new(vec![
+            key(2.0, 4.0, Interpolation::Step),
+            key(0.0, 0.0, Interpolation::Linear),
+            key(1.0, 2.0, Interpolation::Bezier),
+            key(3.0, 1.0, Interpolation::Linear),
+        ]);
+
+        // held before the first and after the last keyframe
+        assert_eq!(track.sample(-1.0), 0.0);
+        assert_eq!(track.sample(5.0), 1.0);
 
+        assert!((track.sample(0.25) - 0.5).abs() < 1e-9);
+        assert_eq!(track.sample(2.5), 4.0);
+        // bezier segments pass through their keyframes
+        assert!((track.sample(1.0) - 2.0).abs() < 1e-9);
+        assert!((track.sample(2.0 - 1e-12) - 4.0).abs() < 1e-6);
+        assert!(track.sample(1.5) > 2.0 && track.sample(1.5) < 4.0);
+    }
+}
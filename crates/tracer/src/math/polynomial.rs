@@ -0,0 +1,137 @@
+//! Real roots of low degree polynomials, coefficients from the highest degree down.
+//! Adapted from Jochen Schwarze's solver in _Graphics Gems I_.
+
+use std::f64::consts::PI;
+
+const EQN_EPS: f64 = 1e-12;
+
+#[inline]
+fn is_zero(x: f64) -> bool {
+    x.abs() < EQN_EPS
+}
+
+/// `a x^2 + b x + c = 0`
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if is_zero(a) {
+        return if is_zero(b) { vec![] } else { vec![-c / b] };
+    }
+
+    let p = b / (2.0 * a);
+    let q = c / a;
+    let delta = p * p - q;
+
+    if is_zero(delta) {
+        vec![-p]
+    } else if delta < 0.0 {
+        vec![]
+    } else {
+        let sqrt_delta = delta.sqrt();
+        vec![-p - sqrt_delta, -p + sqrt_delta]
+    }
+}
+
+/// `a x^3 + b x^2 + c x + d = 0`
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if is_zero(a) {
+        return solve_quadratic(b, c, d);
+    }
+
+    // normal form: x^3 + A x^2 + B x + C = 0
+    let (a, b, c) = (b / a, c / a, d / a);
+
+    // substitute x = y - A/3 to eliminate the quadric term: y^3 + 3p y + 2q = 0
+    let sq_a = a * a;
+    let p = (-sq_a / 3.0 + b) / 3.0;
+    let q = (2.0 / 27.0 * a * sq_a - a * b / 3.0 + c) / 2.0;
+
+    let cb_p = p * p * p;
+    let delta = q * q + cb_p;
+
+    let roots = if is_zero(delta) {
+        if is_zero(q) {
+            vec![0.0]
+        } else {
+            let u = (-q).cbrt();
+            vec![2.0 * u, -u]
+        }
+    } else if delta < 0.0 {
+        // three real roots
+        let phi = (-q / (-cb_p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        vec![
+            t * phi.cos(),
+            -t * (phi + PI / 3.0).cos(),
+            -t * (phi - PI / 3.0).cos(),
+        ]
+    } else {
+        let sqrt_delta = delta.sqrt();
+        let u = (sqrt_delta - q).cbrt();
+        let v = -(sqrt_delta + q).cbrt();
+        vec![u + v]
+    };
+
+    roots.into_iter().map(|y| y - a / 3.0).collect()
+}
+
+/// `a x^4 + b x^3 + c x^2 + d x + e = 0`, roots are sorted.
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if is_zero(a) {
+        return solve_cubic(b, c, d, e);
+    }
+
+    // normal form: x^4 + A x^3 + B x^2 + C x + D = 0
+    let (na, nb, nc, nd) = (b / a, c / a, d / a, e / a);
+
+    // substitute x = y - A/4 to eliminate the cubic term: y^4 + p y^2 + q y + r = 0
+    let sq_a = na * na;
+    let p = -3.0 / 8.0 * sq_a + nb;
+    let q = sq_a * na / 8.0 - na * nb / 2.0 + nc;
+    let r = -3.0 / 256.0 * sq_a * sq_a + sq_a * nb / 16.0 - na * nc / 4.0 + nd;
+
+    let mut roots = if is_zero(r) {
+        // no absolute term: y (y^3 + p y + q) = 0
+        let mut roots = solve_cubic(1.0, 0.0, p, q);
+        roots.push(0.0);
+        roots
+    } else {
+        // solve the resolvent cubic and take its one real root
+        let z = solve_cubic(1.0, -p / 2.0, -r, r * p / 2.0 - q * q / 8.0)[0];
+
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let u = if is_zero(u) {
+            0.0
+        } else if u > 0.0 {
+            u.sqrt()
+        } else {
+            return vec![];
+        };
+        let v = if is_zero(v) {
+            0.0
+        } else if v > 0.0 {
+            v.sqrt()
+        } else {
+            return vec![];
+        };
+
+        let mut roots = solve_quadratic(1.0, if q < 0.0 { -v } else { v }, z - u);
+        roots.extend(solve_quadratic(1.0, if q < 0.0 { v } else { -v }, z + u));
+        roots
+    };
+
+    for root in roots.iter_mut() {
+        *root -= na / 4.0;
+        // polish with a few newton steps on the original polynomial
+        for _ in 0..2 {
+            let x = *root;
+            let f = (((a * x + b) * x + c) * x + d) * x + e;
+            let df = ((4.0 * a * x + 3.0 * b) * x + 2.0 * c) * x + d;
+            if df.abs() > EQN_EPS {
+                *root = x - f / df;
+            }
+        }
+    }
+
+    roots.sort_by(f64::total_cmp);
+    roots
+}
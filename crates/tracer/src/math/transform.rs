@@ -0,0 +1,92 @@
+use super::angles::deg2rad;
+use crate::helpers::types::{mat4, vec3};
+
+/// An affine transform, stored together with its inverse.
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+    pub mat: mat4,
+    pub inv: mat4,
+}
+
+impl Transform {
+    /// ## PANICS if `mat` is not invertible.
+    pub fn new(mat: mat4) -> Self {
+        Self::try_new(mat).expect("Transform should be invertible!")
+    }
+
+    /// `None` if `mat` is not invertible, e.g. scaled to zero.
+    pub fn try_new(mat: mat4) -> Option<Self> {
+        let inv = mat.try_inverse()?;
+        Some(Self { mat, inv })
+    }
+
+    pub fn identity() -> Self {
+        Self {
+            mat: mat4::identity(),
+            inv: mat4::identity(),
+        }
+    }
+
+    pub fn translate(delta: vec3) -> Self {
+        Self {
+            mat: glm::translation(&delta),
+            inv: glm::translation(&-delta),
+        }
+    }
+
+    /// ## PANICS if any component of `factor` is 0.
+    pub fn scale(factor: vec3) -> Self {
+        Self::new(glm::scaling(&factor))
+    }
+
+    /// `angle` in radians, counter-clockwise around `axis`.
+    pub fn rotate(angle: f64, axis: vec3) -> Self {
+        let mat = glm::rotation(angle, &axis);
+        Self {
+            mat,
+            inv: mat.transpose(),
+        }
+    }
+
+    /// Euler angles in degrees, applied around X, then Y, then Z.
+    pub fn rotate_euler(degrees: vec3) -> Self {
+        Self::rotate(deg2rad(degrees.x), vec3::x())
+            .then(&Self::rotate(deg2rad(degrees.y), vec3::y()))
+            .then(&Self::rotate(deg2rad(degrees.z), vec3::z()))
+    }
+
+    /// Scale, then rotate, then translate.
+    pub fn from_trs(translate: vec3, rotate: vec3, scale: vec3) -> Self {
+        Self::scale(scale)
+            .then(&Self::rotate_euler(rotate))
+            .then(&Self::translate(translate))
+    }
+
+    /// Applies `self` first, then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        Self {
+            mat: next.mat * self.mat,
+            inv: self.inv * next.inv,
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self {
+            mat: self.inv,
+            inv: self.mat,
+        }
+    }
+
+    pub fn point(&self, p: vec3) -> vec3 {
+        (self.mat * p.push(1.0)).xyz()
+    }
+
+    pub fn vector(&self, v: vec3) -> vec3 {
+        (self.mat * v.push(0.0)).xyz()
+    }
+
+    /// Normals transform by the inverse transpose; the result is not normalized.
+    pub fn normal(&self, n: vec3) -> vec3 {
+        (self.inv.transpose() * n.push(0.0)).xyz()
+    }
+}
@@ -0,0 +1,342 @@
+use super::{
+    film::Film,
+    ray::{hit::Normal, ray::Ray},
+    region::Region,
+};
+use crate::{
+    camera::camera_lens::LensCamera,
+    entity::scene::Scene,
+    helpers::{
+        traits::Color,
+        types::{color, vec3, GammaColor},
+    },
+};
+use exr::prelude::{
+    AnyChannel, AnyChannels, Encoding, FlatSamples, Image, ImageAttributes, IntegerBounds,
+    Layer, LayerAttributes, SmallVec, WritableImage,
+};
+use image::RgbImage;
+use indicatif::ParallelProgressIterator;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+/// Arbitrary output variables, buffers rendered along the beauty image for compositing,
+/// set by the `[aov]` table of a tracer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Aov {
+    pub passes: Vec<Pass>,
+    #[serde(default)]
+    pub format: AovFormat,
+    /// Path without extension, `_<pass>.png` is appended for every pass or `.exr` once.
+    pub out_path: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum AovFormat {
+    /// An image per pass, remapped to be viewed.
+    #[default]
+    Png,
+    /// A layer per pass in a single file, as rendered.
+    Exr,
+}
+
+/// Taken at the first hit of the ray through the pixel center, zero if nothing is hit,
+/// except for the light passes splatted from the samples of the beauty image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Pass {
+    /// `t` along the camera ray.
+    Depth,
+    Position,
+    /// Shading normal, facing the camera ray.
+    Normal,
+    Albedo,
+    /// Position of the material in the `[[materials]]` table, from 1.
+    MaterialId,
+    /// Index of the entity in the scene, from 1.
+    EntityIndex,
+    /// Light of emitters and the background seen as is.
+    Emission,
+    /// Light scattered once.
+    Direct,
+    /// Light scattered more than once.
+    Indirect,
+}
+
+impl Pass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pass::Depth => "depth",
+            Pass::Position => "position",
+            Pass::Normal => "normal",
+            Pass::Albedo => "albedo",
+            Pass::MaterialId => "material_id",
+            Pass::EntityIndex => "entity_index",
+            Pass::Emission => "emission",
+            Pass::Direct => "direct",
+            Pass::Indirect => "indirect",
+        }
+    }
+
+    /// Names of the EXR channels.
+    pub fn channels(&self) -> &'static [&'static str] {
+        match self {
+            Pass::Depth => &["Z"],
+            Pass::Position | Pass::Normal => &["X", "Y", "Z"],
+            Pass::MaterialId | Pass::EntityIndex => &["id"],
+            _ => &["R", "G", "B"],
+        }
+    }
+
+    /// Split from the radiance of the samples, rather than taken at the first hit.
+    pub fn is_light(&self) -> bool {
+        matches!(self, Pass::Emission | Pass::Direct | Pass::Indirect)
+    }
+
+    /// Channels of the pass at the pixel, scalars in `x`.
+    pub fn value(&self, px: &AovPixel) -> vec3 {
+        match self {
+            Pass::Depth => vec3::new(px.depth, 0.0, 0.0),
+            Pass::Position => px.position,
+            Pass::Normal => px.normal,
+            Pass::Albedo => px.albedo,
+            Pass::MaterialId => vec3::new(px.material_id as f64, 0.0, 0.0),
+            Pass::EntityIndex => vec3::new(px.entity_index as f64, 0.0, 0.0),
+            Pass::Emission => px.emission,
+            Pass::Direct => px.direct,
+            Pass::Indirect => px.indirect,
+        }
+    }
+}
+
+/// Every pass at a pixel.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AovPixel {
+    pub depth: f64,
+    pub position: vec3,
+    pub normal: vec3,
+    pub albedo: color,
+    /// 0 if nothing is hit or the material is not from the table.
+    pub material_id: u32,
+    /// 0 if nothing is hit.
+    pub entity_index: u32,
+    pub emission: color,
+    pub direct: color,
+    pub indirect: color,
+}
+
+impl AovPixel {
+    /// Passes taken at the first hit of `ray`, the light ones are left zero.
+    pub fn first_hit(scene: &Scene, ray: Option<Ray>) -> Self {
+        let Some((i, hit)) = ray.and_then(|ray| scene.hit_entity(ray)) else {
+            return Self::default();
+        };
+        Self {
+            depth: hit.t,
+            position: hit.pos,
+            normal: match hit.normal {
+                Normal::Outward(normal) | Normal::Inward(normal) => normal,
+            },
+            albedo: hit.material.albedo(),
+            material_id: hit.material_id,
+            entity_index: i as u32 + 1,
+            ..Self::default()
+        }
+    }
+}
+
+/// Passes of every pixel, row by row.
+#[derive(Debug, Clone)]
+pub struct AovFilm {
+    pub resolution: glm::UVec2,
+    /// Pixel of the camera image the film starts at, when only a region is rendered.
+    pub offset: glm::UVec2,
+    pub pixels: Vec<AovPixel>,
+}
+
+impl AovFilm {
+    /// Only fills the pixels of `region`, the whole image if not set.
+    /// - `aov_at`: takes pixels of the whole image.
+    pub fn render(
+        resolution: glm::UVec2,
+        region: Option<&Region>,
+        aov_at: impl Fn(u32, u32) -> AovPixel + Sync,
+    ) -> Self {
+        let (offset, size) = match region {
+            Some(region) => region.pixels(resolution),
+            None => (glm::UVec2::zeros(), resolution),
+        };
+
+        let mut pixels = vec![AovPixel::default(); (size.x * size.y) as usize];
+        let width = size.x as usize;
+        pixels
+            .par_iter_mut()
+            .enumerate()
+            .progress()
+            .for_each(|(i, px)| {
+                *px = aov_at(offset.x + (i % width) as u32, offset.y + (i / width) as u32)
+            });
+        Self {
+            resolution: size,
+            offset,
+            pixels,
+        }
+    }
+
+    /// Fills the light passes from the films splatted over the same pixels.
+    pub fn add_light(&mut self, [emission, direct, indirect]: &[Film; 3]) {
+        for (i, px) in self.pixels.iter_mut().enumerate() {
+            px.emission = emission.pixels[i];
+            px.direct = direct.pixels[i];
+            px.indirect = indirect.pixels[i];
+        }
+    }
+
+    /// Writes the passes of `aov`, cropped to the region if any.
+    pub fn save(&self, aov: &Aov, cam: &LensCamera) -> anyhow::Result<()> {
+        match aov.format {
+            AovFormat::Png => {
+                for pass in &aov.passes {
+                    let path = format!("{}_{}.png", aov.out_path, pass.name());
+                    self.viewed(*pass, cam).save(path)?;
+                }
+            }
+            AovFormat::Exr => {
+                let size = (self.resolution.x as usize, self.resolution.y as usize);
+                let layers: Vec<_> = aov
+                    .passes
+                    .iter()
+                    .map(|pass| {
+                        let channels = pass
+                            .channels()
+                            .iter()
+                            .enumerate()
+                            .map(|(i, name)| {
+                                let samples = self
+                                    .pixels
+                                    .iter()
+                                    .map(|px| pass.value(px)[i] as f32)
+                                    .collect();
+                                AnyChannel::new(*name, FlatSamples::F32(samples))
+                            })
+                            .collect();
+                        Layer::new(
+                            size,
+                            LayerAttributes::named(pass.name()),
+                            Encoding::FAST_LOSSLESS,
+                            AnyChannels::sort(SmallVec::from_vec(channels)),
+                        )
+                    })
+                    .collect();
+
+                let attributes = ImageAttributes::new(IntegerBounds::from_dimensions(size));
+                Image::from_layers(attributes, layers)
+                    .write()
+                    .to_file(format!("{}.exr", aov.out_path))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remaps the pass into \[0, 1\], to be viewed:
+    /// - depth: inverse, from white at the nearest pixels to black far away,
+    /// - position: within the positions of most pixels,
+    /// - normal: from \[-1, 1\],
+    /// - albedo: gamma encoded,
+    /// - ids: a color each,
+    /// - light: developed as the beauty image.
+    ///
+    /// Pixels without a hit are black.
+    pub fn viewed(&self, pass: Pass, cam: &LensCamera) -> RgbImage {
+        let values: Vec<vec3> = self.pixels.iter().map(|px| pass.value(px)).collect();
+        let remapped: Vec<color> = match pass {
+            Pass::Depth => {
+                let (near, _) = self.hit_range(pass);
+                self.hit_only(values.iter().map(|v| color::repeat(near.x / v.x)))
+            }
+            Pass::Position => {
+                let (low, high) = self.hit_range(pass);
+                self.hit_only(
+                    values
+                        .iter()
+                        .map(|v| (v - low).component_div(&(high - low))),
+                )
+            }
+            Pass::Normal => self.hit_only(values.iter().map(|v| 0.5 * v.add_scalar(1.0))),
+            Pass::MaterialId | Pass::EntityIndex => {
+                values.iter().map(|v| id_color(v.x as u32)).collect()
+            }
+            Pass::Albedo => values.iter().map(|v| v.to_gamma().vector()).collect(),
+            Pass::Emission | Pass::Direct | Pass::Indirect => {
+                let film = Film {
+                    resolution: self.resolution,
+                    offset: self.offset,
+                    pixels: values,
+                };
+                return film.developed(cam);
+            }
+        };
+
+        let mut img = RgbImage::new(self.resolution.x, self.resolution.y);
+        img.pixels_mut().zip(remapped).for_each(|(px, color)| {
+            let color = color.map(|v| v.clamp(0.0, 1.0));
+            *px = GammaColor {
+                r: color.x,
+                g: color.y,
+                b: color.z,
+            }
+            .quantize_u8()
+            .into()
+        });
+        img
+    }
+
+    /// 2nd and 98th percentiles of the pass over the pixels with a hit, by channel,
+    /// as infinite planes reach much farther than the rest.
+    fn hit_range(&self, pass: Pass) -> (vec3, vec3) {
+        let (mut low, mut high) = (vec3::zeros(), vec3::repeat(1.0));
+        for i in 0..3 {
+            let mut values: Vec<f64> = self
+                .pixels
+                .iter()
+                .filter(|px| px.entity_index > 0)
+                .map(|px| pass.value(px)[i])
+                .collect();
+            if values.is_empty() {
+                continue;
+            }
+            values.sort_by(f64::total_cmp);
+            let at = |q: f64| values[((values.len() - 1) as f64 * q) as usize];
+            low[i] = at(0.02);
+            high[i] = at(0.98).max(low[i] + 1e-9);
+        }
+        (low, high)
+    }
+
+    /// Black where nothing is hit.
+    fn hit_only(&self, colors: impl Iterator<Item = color>) -> Vec<color> {
+        self.pixels
+            .iter()
+            .zip(colors)
+            .map(|(px, color)| {
+                if px.entity_index > 0 {
+                    color
+                } else {
+                    color::zeros()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Scatters ids over distinct colors, black for 0.
+fn id_color(id: u32) -> color {
+    if id == 0 {
+        return color::zeros();
+    }
+    let hash = id.wrapping_mul(0x9E37_79B1);
+    color::new(
+        (hash >> 24) as f64 / 255.0,
+        ((hash >> 16) & 0xFF) as f64 / 255.0,
+        ((hash >> 8) & 0xFF) as f64 / 255.0,
+    )
+}
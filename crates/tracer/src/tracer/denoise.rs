@@ -0,0 +1,109 @@
+use super::{aov::AovFilm, film::Film};
+use crate::helpers::types::color;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+/// Edge-avoiding à-trous wavelet filter, set by the `[denoise]` table of a tracer.
+///
+/// Blurs the radiance over growing, sparser 5x5 kernels before the image is developed,
+/// stopping at edges of the radiance, normal, albedo and depth at the first hit.
+/// What is seen through glass and mirrors is guided by their surface, so it blurs more.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Denoise {
+    /// Kernels of 5, 9, 17 ... pixels wide.
+    #[serde(default = "default_iterations")]
+    pub iterations: u32,
+    /// Of radiance mapped by `c / (1 + c)`, halved every iteration.
+    #[serde(default = "default_sigma_color")]
+    pub sigma_color: f64,
+    #[serde(default = "default_sigma_normal")]
+    pub sigma_normal: f64,
+    #[serde(default = "default_sigma_albedo")]
+    pub sigma_albedo: f64,
+    /// Of the depth relative to the depth of the pixel.
+    #[serde(default = "default_sigma_depth")]
+    pub sigma_depth: f64,
+}
+
+fn default_iterations() -> u32 {
+    5
+}
+
+fn default_sigma_color() -> f64 {
+    1.0
+}
+
+fn default_sigma_normal() -> f64 {
+    0.3
+}
+
+fn default_sigma_albedo() -> f64 {
+    0.1
+}
+
+fn default_sigma_depth() -> f64 {
+    0.1
+}
+
+/// B3 spline, the 5x5 kernel is its outer product.
+const KERNEL: [f64; 5] = [1.0 / 16.0, 1.0 / 4.0, 3.0 / 8.0, 1.0 / 4.0, 1.0 / 16.0];
+
+impl Denoise {
+    /// - `guide`: first hits over the same pixels as `film`.
+    pub fn apply(&self, film: &Film, guide: &AovFilm) -> Film {
+        let (width, height) = (film.resolution.x as i64, film.resolution.y as i64);
+        // keeps fireflies from stopping the filter everywhere
+        let compressed = |c: &color| c.map(|v| v / (1.0 + v));
+        let stop = |distance2: f64, sigma: f64| (-distance2 / (sigma * sigma)).exp();
+
+        let mut pixels = film.pixels.clone();
+        for i in 0..self.iterations {
+            let step = 1_i64 << i;
+            let sigma_color = self.sigma_color * 0.5_f64.powi(i as i32);
+            let src = pixels;
+
+            pixels = (0..src.len())
+                .into_par_iter()
+                .map(|p| {
+                    let (x, y) = (p as i64 % width, p as i64 / width);
+                    let (center, g) = (compressed(&src[p]), &guide.pixels[p]);
+
+                    let mut sum = color::zeros();
+                    let mut total = 0.0;
+                    for (j, ky) in KERNEL.iter().enumerate() {
+                        for (i, kx) in KERNEL.iter().enumerate() {
+                            let qx = x + (i as i64 - 2) * step;
+                            let qy = y + (j as i64 - 2) * step;
+                            if qx < 0 || qx >= width || qy < 0 || qy >= height {
+                                continue;
+                            }
+                            let q = (qy * width + qx) as usize;
+                            let h = &guide.pixels[q];
+
+                            let depth = (h.depth - g.depth) / g.depth.max(1e-9);
+                            let weight = kx
+                                * ky
+                                * stop(
+                                    (compressed(&src[q]) - center).norm_squared(),
+                                    sigma_color,
+                                )
+                                * stop((h.normal - g.normal).norm_squared(), self.sigma_normal)
+                                * stop((h.albedo - g.albedo).norm_squared(), self.sigma_albedo)
+                                * stop(depth * depth, self.sigma_depth);
+                            sum += weight * src[q];
+                            total += weight;
+                        }
+                    }
+                    // the center alone weighs above zero
+                    sum / total
+                })
+                .collect();
+        }
+
+        Film {
+            resolution: film.resolution,
+            offset: film.offset,
+            pixels,
+        }
+    }
+}
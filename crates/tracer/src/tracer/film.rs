@@ -0,0 +1,239 @@
+use crate::{
+    camera::camera_lens::LensCamera,
+    helpers::{
+        traits::Color,
+        types::{color, vec2},
+    },
+};
+use image::{imageops, ImageResult, RgbImage};
+use indicatif::ParallelProgressIterator;
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
+};
+
+use super::{filter::Filter, region::Region};
+
+/// Linear radiance of every pixel, row by row, kept until the image is developed.
+#[derive(Debug, Clone)]
+pub struct Film {
+    pub resolution: glm::UVec2,
+    /// Pixel of the camera image the film starts at, when only a region is rendered.
+    pub offset: glm::UVec2,
+    pub pixels: Vec<color>,
+}
+
+/// Keeps the logarithm finite on black pixels.
+const LOG_DELTA: f64 = 1e-4;
+
+/// Rows of pixels a thread splats at once.
+const STRIP_HEIGHT: u32 = 8;
+
+impl Film {
+    pub fn new(resolution: glm::UVec2) -> Self {
+        Self {
+            resolution,
+            offset: glm::UVec2::zeros(),
+            pixels: vec![color::zeros(); (resolution.x * resolution.y) as usize],
+        }
+    }
+
+    /// Fills every pixel in parallel.
+    pub fn render(resolution: glm::UVec2, color_at: impl Fn(u32, u32) -> color + Sync) -> Self {
+        Self::render_region(resolution, None, color_at)
+    }
+
+    /// Only fills the pixels of `region`, the whole image if not set.
+    /// - `color_at`: takes pixels of the whole image.
+    pub fn render_region(
+        resolution: glm::UVec2,
+        region: Option<&Region>,
+        color_at: impl Fn(u32, u32) -> color + Sync,
+    ) -> Self {
+        let (offset, size) = match region {
+            Some(region) => region.pixels(resolution),
+            None => (glm::UVec2::zeros(), resolution),
+        };
+
+        let mut film = Self {
+            offset,
+            ..Self::new(size)
+        };
+        let width = size.x as usize;
+        film.pixels
+            .par_iter_mut()
+            .enumerate()
+            .progress()
+            .for_each(|(i, px)| {
+                *px = color_at(offset.x + (i % width) as u32, offset.y + (i / width) as u32)
+            });
+        film
+    }
+
+    /// Weights the samples of every pixel of `region` into the pixels around them by `filter`,
+    /// the whole image if not set. Pixels just outside the region are traced too, they reach
+    /// into it.
+    /// - `samples_at`: takes pixels of the whole image, gives the positions of its samples
+    ///   within the pixel, in \[0, 1), and their radiance.
+    pub fn splat(
+        resolution: glm::UVec2,
+        region: Option<&Region>,
+        filter: &Filter,
+        samples_at: impl Fn(u32, u32) -> Vec<(vec2, color)> + Sync,
+    ) -> Self {
+        let [film] = Self::splat_layers(resolution, region, filter, |ix, iy| {
+            samples_at(ix, iy)
+                .into_iter()
+                .map(|(delta, radiance)| (delta, [radiance]))
+                .collect()
+        });
+        film
+    }
+
+    /// As [`Film::splat`], with `N` radiances per sample weighted alike into a film each.
+    pub fn splat_layers<const N: usize>(
+        resolution: glm::UVec2,
+        region: Option<&Region>,
+        filter: &Filter,
+        samples_at: impl Fn(u32, u32) -> Vec<(vec2, [color; N])> + Sync,
+    ) -> [Self; N] {
+        let (offset, size) = match region {
+            Some(region) => region.pixels(resolution),
+            None => (glm::UVec2::zeros(), resolution),
+        };
+        let (film_min, film_max) = (offset, offset + size);
+
+        let radius = filter.radius();
+        let margin = ((radius + 0.5).ceil() as u32).saturating_sub(1);
+        let traced_min = film_min.map(|v| v.saturating_sub(margin));
+        let traced_max = (film_max + glm::UVec2::repeat(margin)).inf(&resolution);
+        // pixels with their center within the radius of `p`, clamped to [min, max)
+        let first = |p: f64, min: u32| (p - 0.5 - radius).ceil().max(min as f64) as u32;
+        let last =
+            |p: f64, max: u32| ((p - 0.5 + radius).floor() + 1.0).clamp(0.0, max as f64) as u32;
+
+        // each strip of traced rows accumulates into the film rows it reaches
+        let n_strip = (traced_max.y - traced_min.y).div_ceil(STRIP_HEIGHT);
+        let strips: Vec<_> = (0..n_strip)
+            .into_par_iter()
+            .progress()
+            .map(|i| {
+                let y0 = traced_min.y + i * STRIP_HEIGHT;
+                let y1 = (y0 + STRIP_HEIGHT).min(traced_max.y);
+                let rows = y0.saturating_sub(margin).max(film_min.y)
+                    ..(y1 + margin).min(film_max.y).max(film_min.y);
+
+                let n_px = (size.x * rows.len() as u32) as usize;
+                let mut sums = vec![[color::zeros(); N]; n_px];
+                let mut weights = vec![0.0; n_px];
+                for iy in y0..y1 {
+                    for ix in traced_min.x..traced_max.x {
+                        for (delta, radiances) in samples_at(ix, iy) {
+                            let p = vec2::new(ix as f64, iy as f64) + delta;
+                            for jy in first(p.y, rows.start)..last(p.y, rows.end) {
+                                for jx in first(p.x, film_min.x)..last(p.x, film_max.x) {
+                                    let weight = filter
+                                        .weight(jx as f64 + 0.5 - p.x, jy as f64 + 0.5 - p.y);
+                                    let i =
+                                        ((jy - rows.start) * size.x + jx - film_min.x) as usize;
+                                    for (sum, radiance) in sums[i].iter_mut().zip(radiances) {
+                                        *sum += weight * radiance;
+                                    }
+                                    weights[i] += weight;
+                                }
+                            }
+                        }
+                    }
+                }
+                (rows, sums, weights)
+            })
+            .collect();
+
+        let n_px = (size.x * size.y) as usize;
+        let mut sums = vec![[color::zeros(); N]; n_px];
+        let mut weights = vec![0.0; n_px];
+        for (rows, strip_sums, strip_weights) in strips {
+            let start = ((rows.start - film_min.y) * size.x) as usize;
+            for (i, (sum, weight)) in strip_sums.into_iter().zip(strip_weights).enumerate() {
+                for (total, sum) in sums[start + i].iter_mut().zip(sum) {
+                    *total += sum;
+                }
+                weights[start + i] += weight;
+            }
+        }
+
+        std::array::from_fn(|k| {
+            let pixels = sums
+                .iter()
+                .zip(&weights)
+                .map(|(sum, &weight)| {
+                    if weight > 0.0 {
+                        (sum[k] / weight).sup(&color::zeros())
+                    } else {
+                        color::zeros()
+                    }
+                })
+                .collect();
+            Self {
+                resolution: size,
+                offset,
+                pixels,
+            }
+        })
+    }
+
+    pub fn at(&self, ix: u32, iy: u32) -> color {
+        self.pixels[(iy * self.resolution.x + ix) as usize]
+    }
+
+    /// Geometric mean of the luminance, what auto exposure meters.
+    pub fn average_luminance(&self) -> f64 {
+        let sum: f64 = self
+            .pixels
+            .iter()
+            .map(|px| (LOG_DELTA + luminance(px)).ln())
+            .sum();
+        (sum / self.pixels.len() as f64).exp()
+    }
+
+    /// Exposes, vignettes and gamma encodes the film as the camera sets.
+    pub fn developed(&self, cam: &LensCamera) -> RgbImage {
+        let scale = match &cam.exposure {
+            Some(exposure) => exposure.scale(cam.f_number(), || self.average_luminance()),
+            None => 1.0,
+        };
+
+        let mut img = RgbImage::new(self.resolution.x, self.resolution.y);
+        img.enumerate_pixels_mut().for_each(|(ix, iy, px)| {
+            let vignetting = cam.vignetting(self.offset.x + ix, self.offset.y + iy);
+            let color = self.at(ix, iy) * scale * vignetting;
+            *px = color.to_gamma().quantize_u8().into()
+        });
+        img
+    }
+
+    /// Develops the film into `path`, pasted over the image there if `region` composites.
+    pub fn save(
+        &self,
+        cam: &LensCamera,
+        path: &str,
+        region: Option<&Region>,
+    ) -> ImageResult<()> {
+        let img = self.developed(cam);
+        if !region.is_some_and(|region| region.composite) {
+            return img.save(path);
+        }
+
+        let mut full = image::open(path)
+            .map(|existing| existing.to_rgb8())
+            .ok()
+            .filter(|existing| existing.dimensions() == (cam.resolution.x, cam.resolution.y))
+            .unwrap_or_else(|| RgbImage::new(cam.resolution.x, cam.resolution.y));
+        imageops::replace(&mut full, &img, self.offset.x as i64, self.offset.y as i64);
+        full.save(path)
+    }
+}
+
+/// Relative luminance of linear sRGB.
+pub fn luminance(c: &color) -> f64 {
+    0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Reconstruction filter samples are splatted with, set by the `[filter]` table of a tracer.
+/// Separable, `radius` is in pixels from the pixel center.
+///
+/// Mitchell and Lanczos have negative lobes, pixels they ring below zero are clamped black.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Filter {
+    /// The average of the samples within the pixel for a radius of 0.5.
+    Box {
+        #[serde(default = "default_box_radius")]
+        radius: f64,
+    },
+    Tent {
+        #[serde(default = "default_tent_radius")]
+        radius: f64,
+    },
+    /// Shifted down to reach zero at the radius.
+    Gaussian {
+        #[serde(default = "default_gaussian_radius")]
+        radius: f64,
+        #[serde(default = "default_sigma")]
+        sigma: f64,
+    },
+    /// The cubic of Mitchell and Netravali, `b` and `c` trade blur for ringing.
+    Mitchell {
+        #[serde(default = "default_mitchell_radius")]
+        radius: f64,
+        #[serde(default = "default_mitchell_bc")]
+        b: f64,
+        #[serde(default = "default_mitchell_bc")]
+        c: f64,
+    },
+    /// Sinc windowed by a sinc stretched to the radius.
+    Lanczos {
+        #[serde(default = "default_lanczos_radius")]
+        radius: f64,
+    },
+}
+
+fn default_box_radius() -> f64 {
+    0.5
+}
+
+fn default_tent_radius() -> f64 {
+    1.0
+}
+
+fn default_gaussian_radius() -> f64 {
+    1.5
+}
+
+fn default_sigma() -> f64 {
+    0.5
+}
+
+fn default_mitchell_radius() -> f64 {
+    2.0
+}
+
+fn default_mitchell_bc() -> f64 {
+    1.0 / 3.0
+}
+
+fn default_lanczos_radius() -> f64 {
+    3.0
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::Box {
+            radius: default_box_radius(),
+        }
+    }
+}
+
+impl Filter {
+    /// ### In pixels
+    pub fn radius(&self) -> f64 {
+        match self {
+            Filter::Box { radius }
+            | Filter::Tent { radius }
+            | Filter::Gaussian { radius, .. }
+            | Filter::Mitchell { radius, .. }
+            | Filter::Lanczos { radius } => *radius,
+        }
+    }
+
+    /// Weight of a sample `dx`, `dy` pixels away from a pixel center.
+    pub fn weight(&self, dx: f64, dy: f64) -> f64 {
+        self.weight_1d(dx) * self.weight_1d(dy)
+    }
+
+    fn weight_1d(&self, x: f64) -> f64 {
+        let x = x.abs();
+        match *self {
+            Filter::Box { radius } => {
+                if x <= radius {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Filter::Tent { radius } => (radius - x).max(0.0),
+            Filter::Gaussian { radius, sigma } => {
+                let gaussian = |x: f64| (-x * x / (2.0 * sigma * sigma)).exp();
+                (gaussian(x) - gaussian(radius)).max(0.0)
+            }
+            Filter::Mitchell { radius, b, c } => {
+                // the cubic spans [0, 2]
+                let x = 2.0 * x / radius;
+                if x >= 2.0 {
+                    0.0
+                } else if x >= 1.0 {
+                    ((-b - 6.0 * c) * x.powi(3)
+                        + (6.0 * b + 30.0 * c) * x.powi(2)
+                        + (-12.0 * b - 48.0 * c) * x
+                        + (8.0 * b + 24.0 * c))
+                        / 6.0
+                } else {
+                    ((12.0 - 9.0 * b - 6.0 * c) * x.powi(3)
+                        + (-18.0 + 12.0 * b + 6.0 * c) * x.powi(2)
+                        + (6.0 - 2.0 * b))
+                        / 6.0
+                }
+            }
+            Filter::Lanczos { radius } => {
+                if x >= radius {
+                    0.0
+                } else {
+                    sinc(x) * sinc(x / radius)
+                }
+            }
+        }
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x < 1e-5 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
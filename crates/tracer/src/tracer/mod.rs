@@ -1,2 +1,97 @@
+pub mod aov;
+pub mod denoise;
+pub mod film;
+pub mod filter;
 pub mod ray;
+pub mod region;
 pub mod tracers;
+
+#[cfg(test)]
+pub mod tests {
+    use glm::UVec2;
+
+    use rand::rngs::ThreadRng;
+
+    use super::{
+        aov::{AovFilm, AovPixel},
+        denoise::Denoise,
+        film::Film,
+        filter::Filter,
+        region::{Rect, Region},
+    };
+    use crate::{
+        helpers::types::{color, vec2},
+        math::distributions::sample_uniform_01,
+    };
+
+    #[test]
+    fn test_filters_keep_flat_radiance() {
+        let radiance = color::new(0.2, 0.5, 0.8);
+        let samples = |_, _| {
+            [(0.1, 0.3), (0.6, 0.2), (0.4, 0.9), (0.8, 0.7)]
+                .map(|(dx, dy)| (vec2::new(dx, dy), radiance))
+                .to_vec()
+        };
+        let region = Region {
+            rect: Rect::Pixels {
+                min: UVec2::new(3, 2),
+                max: UVec2::new(9, 7),
+            },
+            composite: false,
+        };
+
+        for filter in [
+            "type = \"Box\"",
+            "type = \"Tent\"",
+            "type = \"Gaussian\"",
+            "type = \"Mitchell\"",
+            "type = \"Lanczos\"",
+        ] {
+            let filter: Filter = toml::from_str(filter).unwrap();
+            for region in [None, Some(&region)] {
+                let film = Film::splat(UVec2::new(12, 10), region, &filter, samples);
+                for px in &film.pixels {
+                    assert!((px - radiance).abs().max() < 1e-9, "{:?} {:?}", filter, px);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_denoise_flat_noise() {
+        let resolution = UVec2::new(32, 32);
+        let noisy = Film::render(resolution, |_, _| {
+            let mut rng = ThreadRng::default();
+            color::repeat(0.5 + 0.4 * (sample_uniform_01(&mut rng) - 0.5))
+        });
+        let guide = AovFilm::render(resolution, None, |_, _| {
+            let mut rng = ThreadRng::default();
+            AovPixel {
+                depth: 1.0 + 1e-3 * sample_uniform_01(&mut rng),
+                ..AovPixel::default()
+            }
+        });
+        let denoise: Denoise = toml::from_str("").unwrap();
+        let denoised = denoise.apply(&noisy, &guide);
+
+        let stats = |film: &Film| {
+            let n = film.pixels.len() as f64;
+            let mean = film.pixels.iter().map(|px| px.x).sum::<f64>() / n;
+            let var = film
+                .pixels
+                .iter()
+                .map(|px| (px.x - mean).powi(2))
+                .sum::<f64>()
+                / n;
+            (mean, var)
+        };
+        let ((mean, var), (denoised_mean, denoised_var)) = (stats(&noisy), stats(&denoised));
+        assert!(
+            (mean - denoised_mean).abs() < 0.01,
+            "{} {}",
+            mean,
+            denoised_mean
+        );
+        assert!(denoised_var < 0.1 * var, "{} {}", var, denoised_var);
+    }
+}
@@ -26,6 +26,8 @@ pub struct Hit {
     pub in_dir: vec3,
     pub pos: vec3,
     pub material: FragMaterial,
+    /// [`Material::id`](crate::materials::material::Material::id) of the entity hit.
+    pub material_id: u32,
     pub t: f64,
 
     // Normal
@@ -98,6 +100,10 @@ impl Hit {
                 let dir = sample_on_sphere(rng);
                 Some((color::zeros(), Ray::new(self.pos, dir, IGNORE_HIT_EPS)))
             }
+            FragMaterial::Isotropic { albedo } => {
+                let dir = sample_on_sphere(rng);
+                Some((albedo, Ray::new(self.pos, dir, IGNORE_HIT_EPS)))
+            }
             FragMaterial::Transparent => {
                 // exactly the same ray, only origin pos is changed
                 Some((
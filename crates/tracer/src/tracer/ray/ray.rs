@@ -5,6 +5,8 @@ pub struct Ray {
     pub orig: vec3,
     pub dir: vec3,
     pub tmin: f64,
+    /// Animation time the ray travels at, sampled within the camera shutter.
+    pub time: f64,
 }
 
 impl Ray {
@@ -13,9 +15,14 @@ impl Ray {
             orig,
             dir: dir.p_normalize(),
             tmin,
+            time: 0.0,
         }
     }
 
+    pub fn with_time(self, time: f64) -> Self {
+        Self { time, ..self }
+    }
+
     pub fn at(&self, t: f64) -> vec3 {
         self.orig + t * self.dir
     }
@@ -0,0 +1,59 @@
+use crate::helpers::types::vec2;
+use serde::{Deserialize, Serialize};
+
+/// Part of the image to trace, set by the `[region]` table of a tracer.
+///
+/// Auto exposure only meters the pixels of the region.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Region {
+    #[serde(flatten)]
+    pub rect: Rect,
+    /// Pastes the region over the image already at `out_path` instead of writing it cropped.
+    /// A missing image or one of another resolution starts out black.
+    #[serde(default)]
+    pub composite: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Rect {
+    /// ### In pixels
+    /// `max` excluded.
+    Pixels { min: glm::UVec2, max: glm::UVec2 },
+    /// In \[0, 1\] of the image size, from the top left corner.
+    Crop { min: vec2, max: vec2 },
+}
+
+impl Region {
+    /// Top left pixel and size of the region, clamped to the image.
+    /// ## PANICS if the region is empty.
+    pub fn pixels(&self, resolution: glm::UVec2) -> (glm::UVec2, glm::UVec2) {
+        if self.is_empty(resolution) {
+            panic!("Empty render region: {:?}", self.rect);
+        }
+        let (min, max) = self.corners(resolution);
+        (min, max - min)
+    }
+
+    /// No pixel of the image inside.
+    pub fn is_empty(&self, resolution: glm::UVec2) -> bool {
+        let (min, max) = self.corners(resolution);
+        max.x <= min.x || max.y <= min.y
+    }
+
+    /// Top left and bottom right pixels, the latter excluded and clamped to the image.
+    fn corners(&self, resolution: glm::UVec2) -> (glm::UVec2, glm::UVec2) {
+        let (min, max) = match &self.rect {
+            Rect::Pixels { min, max } => (*min, *max),
+            Rect::Crop { min, max } => {
+                let res = vec2::new(resolution.x as f64, resolution.y as f64);
+                let scaled = |p: &vec2| {
+                    let p = p.component_mul(&res);
+                    glm::UVec2::new(p.x.round().max(0.0) as u32, p.y.round().max(0.0) as u32)
+                };
+                (scaled(min), scaled(max))
+            }
+        };
+        (min, max.inf(&resolution))
+    }
+}
@@ -3,14 +3,14 @@ use crate::{
     entity::animated_scene::AnimatedScene,
     helpers::{
         constants::MAX_NUM_REFLECTION,
-        types::{color, vec3},
+        types::{color, vec2, vec3},
     },
-    math::{distributions::sample_uniform_01, panics::PanickingNormalize},
-    tracer::ray::ray::Ray,
+    math::distributions::sample_uniform_01,
+    tracer::{filter::Filter, ray::ray::Ray},
 };
 use rand::rngs::ThreadRng;
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::{fs, path::PathBuf};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TracerAnimatedBuilder {
@@ -18,7 +18,10 @@ pub struct TracerAnimatedBuilder {
     scene: String,
     spp: usize,
     out_path: String,
+    #[serde(default)]
+    filter: Filter,
     n_step: u32,
+    frames: Option<FrameSequence>,
 }
 
 pub struct TracerAnimated {
@@ -26,7 +29,59 @@ pub struct TracerAnimated {
     pub scene: AnimatedScene,
     pub spp: usize,
     pub out_path: String,
+    /// Weights samples into the pixels around them.
+    pub filter: Filter,
     pub n_step: u32,
+    /// Renders an animation frame by frame instead of a single image.
+    pub frames: Option<FrameSequence>,
+}
+
+/// Frames evenly spread over an animation time range, written as numbered images
+/// `frame_0001.png`, `frame_0002.png`, ... into `out_dir`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FrameSequence {
+    pub n_frames: u32,
+    /// Animation times of the first and the last frame.
+    pub time: vec2,
+    pub out_dir: String,
+    /// Fraction of the time between two frames the shutter stays open for,
+    /// 0 for sharp frames.
+    #[serde(default)]
+    pub motion_blur: f64,
+    /// Frames already on disk are not rendered again, so interrupted jobs resume.
+    #[serde(default = "default_skip_existing")]
+    pub skip_existing: bool,
+}
+
+fn default_skip_existing() -> bool {
+    true
+}
+
+impl FrameSequence {
+    /// Time between two frames.
+    pub fn frame_duration(&self) -> f64 {
+        if self.n_frames <= 1 {
+            0.0
+        } else {
+            (self.time.y - self.time.x) / (self.n_frames - 1) as f64
+        }
+    }
+
+    /// `i` in \[0, n_frames).
+    pub fn frame_time(&self, i: u32) -> f64 {
+        self.time.x + self.frame_duration() * i as f64
+    }
+
+    /// Shutter interval of frame `i`, opening at the frame time.
+    pub fn shutter(&self, i: u32) -> vec2 {
+        let t = self.frame_time(i);
+        vec2::new(t, t + self.motion_blur * self.frame_duration())
+    }
+
+    /// Image of frame `i`, numbered from 1.
+    pub fn path(&self, i: u32) -> PathBuf {
+        PathBuf::from(&self.out_dir).join(format!("frame_{:04}.png", i + 1))
+    }
 }
 
 impl TracerAnimated {
@@ -34,14 +89,18 @@ impl TracerAnimated {
         let builder: TracerAnimatedBuilder =
             toml::from_str(fs::read_to_string(path).unwrap().as_str()).unwrap();
 
-        let cam = LensCameraBuilder::configured(&builder.camera)?.build();
         let scene = AnimatedScene::configured(&builder.scene, builder.n_step)?;
+        let cam = LensCameraBuilder::configured(&builder.camera)?
+            .build()
+            .autofocused(|ray| scene.hit_by(ray));
         Ok(Self {
             cam,
             scene,
             spp: builder.spp,
             out_path: builder.out_path,
+            filter: builder.filter,
             n_step: builder.n_step,
+            frames: builder.frames,
         })
     }
 }
@@ -53,26 +112,28 @@ impl TracerAnimated {
             .collect()
     }
 
-    pub fn color_at(&self, ix: u32, iy: u32, rng: &mut ThreadRng) -> color {
-        let pixel_lefttop = self.cam.image_space.pixel_lefttop_at(ix, iy);
-
-        let deltas = &self.sample_pixel_delta(self.spp, rng);
-
-        let mut color = vec3::zeros();
-
-        for (dx, dy) in deltas {
-            let pixel = pixel_lefttop + self.cam.image_space.pixel_offset(*dx, *dy);
-
-            let cam_pos = self.cam.sample_position(rng);
-            let v = pixel - cam_pos;
-            // let mag = v.magnitude();
-            let dir = v.p_normalize();
-            let ray = Ray::new(cam_pos, dir, 0.0);
-
-            color += self.color_from_ray(ray, rng, 0);
-        }
+    /// Radiance of samples spread uniformly within the pixel, with their position in it.
+    pub fn samples_at(&self, ix: u32, iy: u32, rng: &mut ThreadRng) -> Vec<(vec2, color)> {
+        self.sample_pixel_delta(self.spp, rng)
+            .into_iter()
+            .map(|(dx, dy)| {
+                let radiance = match self.cam.sample_ray(ix, iy, dx, dy, rng) {
+                    Some(ray) => self.color_from_ray(ray, rng, 0),
+                    // black outside of the projection
+                    None => color::zeros(),
+                };
+                (vec2::new(dx, dy), radiance)
+            })
+            .collect()
+    }
 
-        color / self.spp as f64
+    /// Average of the samples within the pixel.
+    pub fn color_at(&self, ix: u32, iy: u32, rng: &mut ThreadRng) -> color {
+        let samples = self.samples_at(ix, iy, rng);
+        samples
+            .iter()
+            .fold(color::zeros(), |total, (_, radiance)| total + radiance)
+            / self.spp as f64
     }
 }
 
@@ -83,10 +144,14 @@ impl TracerAnimated {
             return color::zeros();
         }
 
-        if let Some(hit) = self.scene.hit_by(ray) {
+        if let Some(hit) = self.scene.hit_through_atmosphere(ray, rng) {
             if let Some((attenuation, scattered_ray)) = hit.scatter(rng) {
                 // bounce from other light ray
-                attenuation.component_mul(&self.color_from_ray(scattered_ray, rng, depth + 1))
+                attenuation.component_mul(&self.color_from_ray(
+                    scattered_ray.with_time(ray.time),
+                    rng,
+                    depth + 1,
+                ))
             } else {
                 // absorbed
                 color::zeros()
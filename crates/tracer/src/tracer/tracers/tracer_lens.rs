@@ -3,10 +3,16 @@ use crate::{
     entity::scene::Scene,
     helpers::{
         constants::MAX_NUM_REFLECTION,
-        types::{color, vec3},
+        types::{color, vec2, vec3},
+    },
+    math::distributions::sample_uniform_01,
+    tracer::{
+        aov::{Aov, AovPixel},
+        denoise::Denoise,
+        filter::Filter,
+        ray::ray::Ray,
+        region::Region,
     },
-    math::{distributions::sample_uniform_01, panics::PanickingNormalize},
-    tracer::ray::ray::Ray,
 };
 use rand::rngs::ThreadRng;
 use serde::{Deserialize, Serialize};
@@ -18,6 +24,11 @@ pub struct TracerLensBuilder {
     scene: String,
     spp: usize,
     out_path: String,
+    #[serde(default)]
+    filter: Filter,
+    region: Option<Region>,
+    denoise: Option<Denoise>,
+    aov: Option<Aov>,
 }
 
 pub struct TracerLens {
@@ -25,6 +36,14 @@ pub struct TracerLens {
     pub scene: Scene,
     pub spp: usize,
     pub out_path: String,
+    /// Weights samples into the pixels around them.
+    pub filter: Filter,
+    /// Only traces part of the image.
+    pub region: Option<Region>,
+    /// Filters the noise out before the image is developed.
+    pub denoise: Option<Denoise>,
+    /// Passes rendered along the image.
+    pub aov: Option<Aov>,
 }
 
 impl TracerLens {
@@ -32,13 +51,19 @@ impl TracerLens {
         let builder: TracerLensBuilder =
             toml::from_str(fs::read_to_string(path).unwrap().as_str()).unwrap();
 
-        let cam = LensCameraBuilder::configured(&builder.camera)?.build();
         let scene = Scene::configured(&builder.scene)?;
+        let cam = LensCameraBuilder::configured(&builder.camera)?
+            .build()
+            .autofocused(|ray| scene.hit_by(ray));
         Ok(Self {
             cam,
             scene,
             spp: builder.spp,
             out_path: builder.out_path,
+            filter: builder.filter,
+            region: builder.region,
+            denoise: builder.denoise,
+            aov: builder.aov,
         })
     }
 }
@@ -50,48 +75,81 @@ impl TracerLens {
             .collect()
     }
 
-    pub fn color_at(&self, ix: u32, iy: u32, rng: &mut ThreadRng) -> color {
-        let pixel_lefttop = self.cam.image_space.pixel_lefttop_at(ix, iy);
-
-        let deltas: Vec<_> = self.sample_pixel_delta(self.spp, rng);
-
-        let mut color = vec3::zeros();
-        for (dx, dy) in deltas {
-            let pixel = pixel_lefttop + self.cam.image_space.pixel_offset(dx, dy);
+    /// Passes at the first hit of the ray through the pixel center, also what guides denoising.
+    pub fn first_hit_at(&self, ix: u32, iy: u32, rng: &mut ThreadRng) -> AovPixel {
+        AovPixel::first_hit(&self.scene, self.cam.sample_ray(ix, iy, 0.5, 0.5, rng))
+    }
 
-            let cam_pos = self.cam.sample_position(rng);
-            let v = pixel - cam_pos;
-            // let mag = v.magnitude();
-            let dir = v.p_normalize();
-            let ray = Ray::new(cam_pos, dir, 0.0);
+    /// Radiance of samples spread uniformly within the pixel, with their position in it.
+    pub fn samples_at(&self, ix: u32, iy: u32, rng: &mut ThreadRng) -> Vec<(vec2, color)> {
+        self.light_samples_at(ix, iy, rng)
+            .into_iter()
+            .map(|(delta, [radiance, ..])| (delta, radiance))
+            .collect()
+    }
 
-            color += self.color_from_ray(ray, rng, 0);
-        }
+    /// As [`TracerLens::samples_at`], the radiance followed by its split from
+    /// [`TracerLens::light_by_bounce`], to splat the light passes along the image.
+    pub fn light_samples_at(
+        &self,
+        ix: u32,
+        iy: u32,
+        rng: &mut ThreadRng,
+    ) -> Vec<(vec2, [color; 4])> {
+        self.sample_pixel_delta(self.spp, rng)
+            .into_iter()
+            .map(|(dx, dy)| {
+                let [seen, direct, indirect] = match self.cam.sample_ray(ix, iy, dx, dy, rng) {
+                    Some(ray) => self.light_by_bounce(ray, rng, 0),
+                    // black outside of the projection
+                    None => [color::zeros(); 3],
+                };
+                (
+                    vec2::new(dx, dy),
+                    [seen + direct + indirect, seen, direct, indirect],
+                )
+            })
+            .collect()
+    }
 
-        color / self.spp as f64
+    /// Average of the samples within the pixel.
+    pub fn color_at(&self, ix: u32, iy: u32, rng: &mut ThreadRng) -> color {
+        let samples = self.samples_at(ix, iy, rng);
+        samples
+            .iter()
+            .fold(color::zeros(), |total, (_, radiance)| total + radiance)
+            / self.spp as f64
     }
 }
 
 impl TracerLens {
     /// FIXME: modify this
     pub fn color_from_ray(&self, ray: Ray, rng: &mut ThreadRng, depth: u32) -> vec3 {
+        let [seen, direct, indirect] = self.light_by_bounce(ray, rng, depth);
+        seen + direct + indirect
+    }
+
+    /// Light along the ray split by how many times it scattered before: none, for emitters
+    /// and the background seen as is, once, the direct lighting, and more, the indirect.
+    pub fn light_by_bounce(&self, ray: Ray, rng: &mut ThreadRng, depth: u32) -> [color; 3] {
+        let mut light = [color::zeros(); 3];
         if depth >= MAX_NUM_REFLECTION {
-            return color::zeros();
+            return light;
         }
 
-        if let Some(hit) = self.scene.hit_by(ray) {
-            let emitted = hit.emit();
-            let scattered = if let Some((attenuation, scattered_ray)) = hit.scatter(rng) {
+        let bounce = (depth as usize).min(2);
+        if let Some(hit) = self.scene.hit_through_atmosphere(ray, rng) {
+            if let Some((attenuation, scattered_ray)) = hit.scatter(rng) {
                 // bounce from other light ray
-                attenuation.component_mul(&self.color_from_ray(scattered_ray, rng, depth + 1))
-            } else {
-                // absorbed
-                color::zeros()
-            };
-
-            emitted + scattered
+                light = self
+                    .light_by_bounce(scattered_ray.with_time(ray.time), rng, depth + 1)
+                    .map(|c| attenuation.component_mul(&c));
+            }
+            light[bounce] += hit.emit();
         } else {
-            self.scene.background.color(ray.dir)
+            light[bounce] += self.scene.background.color(ray.dir);
         }
+
+        light
     }
 }